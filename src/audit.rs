@@ -0,0 +1,183 @@
+use crate::defaults;
+use crate::lease::LeaseState;
+use crate::watch::watch_lease;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio_retry::strategy::ExponentialBackoff;
+
+/// Something noteworthy that happened to an audited lease.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuditEvent {
+    /// The lease changed hands; `to` is None when it was released.
+    Transition {
+        lease_name: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// The holder didn't renew within the overdue threshold, though the lease hasn't expired yet.
+    RenewalOverdue { lease_name: String, holder: String },
+    /// The holder didn't renew and the lease expired.
+    Expired { lease_name: String, holder: String },
+}
+
+/// Watches a set of leases without ever writing to them, and reports transitions, overdue
+/// renewals and expiries. Meant for operators monitoring leases of third-party components.
+pub struct LeaseAuditor {
+    api: kube::Api<LeaseObject>,
+    lease_names: Vec<String>,
+    overdue_percent: u32,
+    expo: ExponentialBackoff,
+}
+
+struct Tracked {
+    lease_state: LeaseState,
+    overdue_reported: bool,
+    expiry_reported: bool,
+}
+
+impl Tracked {
+    fn overdue_at(&self, overdue_percent: u32) -> chrono::DateTime<chrono::Utc> {
+        self.lease_state.renew_time()
+            + self.lease_state.lease_duration() * overdue_percent as i32 / 100
+    }
+}
+
+struct AuditState {
+    states: BoxStream<'static, LeaseState>,
+    tracked: HashMap<String, Tracked>,
+    pending: VecDeque<AuditEvent>,
+    overdue_percent: u32,
+}
+
+impl LeaseAuditor {
+    pub fn new(api: kube::Api<LeaseObject>, lease_names: Vec<String>) -> Self {
+        Self {
+            api,
+            lease_names,
+            overdue_percent: 60,
+            expo: defaults::current().expo,
+        }
+    }
+
+    /// Report a renewal as overdue once `percent` of the lease duration passed since the last one.
+    /// Default is 60.
+    pub fn with_overdue_percent(mut self, percent: u32) -> Self {
+        self.overdue_percent = percent;
+        self
+    }
+
+    /// Customize backoff of watch re-establishment. Default is
+    /// `ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1))`,
+    /// unless changed by [crate::defaults].
+    pub fn with_expo_backoff(mut self, expo: ExponentialBackoff) -> Self {
+        self.expo = expo;
+        self
+    }
+
+    /// Endless stream of audit events of all audited leases.
+    pub fn events(&self) -> impl Stream<Item = AuditEvent> + Send + 'static {
+        let states = stream::select_all(self.lease_names.iter().map(|lease_name| {
+            watch_lease(self.api.clone(), lease_name.clone(), self.expo.clone()).boxed()
+        }))
+        .boxed();
+        let audit_state = AuditState {
+            states,
+            tracked: HashMap::new(),
+            pending: VecDeque::new(),
+            overdue_percent: self.overdue_percent,
+        };
+        stream::unfold(audit_state, |mut audit_state| async move {
+            loop {
+                if let Some(event) = audit_state.pending.pop_front() {
+                    return Some((event, audit_state));
+                }
+                let next_deadline = audit_state.next_deadline();
+                let sleep = next_deadline
+                    .map(|d| (d - chrono::Utc::now()).to_std().unwrap_or(Duration::ZERO))
+                    .unwrap_or(Duration::ZERO);
+                tokio::select! {
+                    lease_state = audit_state.states.next() => match lease_state {
+                        Some(lease_state) => audit_state.observe(lease_state),
+                        None => return None,
+                    },
+                    _ = tokio::time::sleep(sleep), if next_deadline.is_some() => {
+                        audit_state.check_deadlines()
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl AuditState {
+    fn observe(&mut self, lease_state: LeaseState) {
+        let lease_name = lease_state.lease_name().to_string();
+        let tracked = match self.tracked.remove(&lease_name) {
+            None => Tracked {
+                lease_state,
+                overdue_reported: false,
+                expiry_reported: false,
+            },
+            Some(old) => {
+                if old.lease_state.holder() != lease_state.holder() {
+                    self.pending.push_back(AuditEvent::Transition {
+                        lease_name: lease_name.clone(),
+                        from: old.lease_state.holder().map(String::from),
+                        to: lease_state.holder().map(String::from),
+                    });
+                }
+                let renewed = old.lease_state.holder() != lease_state.holder()
+                    || old.lease_state.renew_time() != lease_state.renew_time();
+                Tracked {
+                    lease_state,
+                    overdue_reported: old.overdue_reported && !renewed,
+                    expiry_reported: old.expiry_reported && !renewed,
+                }
+            }
+        };
+        self.tracked.insert(lease_name, tracked);
+        self.check_deadlines();
+    }
+
+    fn check_deadlines(&mut self) {
+        let now = chrono::Utc::now();
+        for (lease_name, tracked) in self.tracked.iter_mut() {
+            let holder = match tracked.lease_state.holder() {
+                Some(holder) => holder.to_string(),
+                None => continue,
+            };
+            if !tracked.expiry_reported && tracked.lease_state.expires_at() <= now {
+                tracked.expiry_reported = true;
+                tracked.overdue_reported = true;
+                self.pending.push_back(AuditEvent::Expired {
+                    lease_name: lease_name.clone(),
+                    holder,
+                });
+            } else if !tracked.overdue_reported && tracked.overdue_at(self.overdue_percent) <= now {
+                tracked.overdue_reported = true;
+                self.pending.push_back(AuditEvent::RenewalOverdue {
+                    lease_name: lease_name.clone(),
+                    holder,
+                });
+            }
+        }
+    }
+
+    fn next_deadline(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.tracked
+            .values()
+            .filter(|t| t.lease_state.holder().is_some())
+            .filter_map(|t| {
+                if !t.overdue_reported {
+                    Some(t.overdue_at(self.overdue_percent))
+                } else if !t.expiry_reported {
+                    Some(t.lease_state.expires_at())
+                } else {
+                    None
+                }
+            })
+            .min()
+    }
+}
@@ -0,0 +1,119 @@
+use crate::lease::LeaseState;
+use crate::task::spawn_named;
+use futures::future::{AbortHandle, Abortable};
+use futures::stream::{Stream, StreamExt};
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use kube::api::ListParams;
+use kube::runtime::watcher::{watcher, Event};
+use kube::ResourceExt;
+use std::convert::TryFrom;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio_retry::strategy::ExponentialBackoff;
+
+/// States buffered for slow subscribers before they start missing updates.
+const CAPACITY: usize = 1024;
+
+/// Single watch of all leases matching a label selector, shared by many locks instead of each
+/// opening its own (see [LeaseLock::with_bulk_watch](crate::LeaseLock::with_bulk_watch)), for
+/// processes handling hundreds of per-item locks. Clones share the watch, which stops once the
+/// last clone is dropped.
+#[derive(Clone)]
+pub struct BulkWatch {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    states_tx: broadcast::Sender<LeaseState>,
+    watch: AbortHandle,
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.watch.abort();
+    }
+}
+
+impl BulkWatch {
+    /// Start watching leases matching `label_selector`, e.g. `app=my-app`.
+    /// Errors are retried with `expo` backoff.
+    pub fn new(
+        api: kube::Api<LeaseObject>,
+        label_selector: &str,
+        expo: ExponentialBackoff,
+    ) -> Self {
+        let (states_tx, _) = broadcast::channel(CAPACITY);
+        let (watch, watch_reg) = AbortHandle::new_pair();
+        let mut events = watcher(api, ListParams::default().labels(label_selector)).boxed();
+        let tx = states_tx.clone();
+        let selector = label_selector.to_string();
+        spawn_named(
+            &format!("lease-bulk-watch:{}", label_selector),
+            None,
+            Abortable::new(
+                async move {
+                    let mut backoff = expo.clone();
+                    while let Some(event) = events.next().await {
+                        let lease_objs = match event {
+                            Ok(Event::Applied(lo)) => vec![lo],
+                            Ok(Event::Restarted(los)) => los,
+                            Ok(Event::Deleted(_)) => continue,
+                            Err(e) => {
+                                let delay = backoff.next().expect("infinite backoff");
+                                log::warn!(
+                                    "bulk_watch({}) => {}:backoff({:?})",
+                                    &selector,
+                                    e,
+                                    delay
+                                );
+                                tokio::time::sleep(delay).await;
+                                continue;
+                            }
+                        };
+                        backoff = expo.clone();
+                        for lease_obj in lease_objs {
+                            let lease_name = lease_obj.name();
+                            match LeaseState::try_from(lease_obj) {
+                                Ok(lease_state) => {
+                                    let _ = tx.send(lease_state);
+                                }
+                                Err(e) => log::error!("{}.bulk_watch() => {}", lease_name, e),
+                            }
+                        }
+                    }
+                },
+                watch_reg,
+            ),
+        );
+        Self {
+            inner: Arc::new(Inner { states_tx, watch }),
+        }
+    }
+
+    /// Endless stream of observed states of `lease_name`, from the time of subscription.
+    pub(crate) fn subscribe(
+        &self,
+        lease_name: String,
+    ) -> impl Stream<Item = LeaseState> + Send + 'static {
+        let states_rx = self.inner.states_tx.subscribe();
+        // hold the watch alive as long as the stream
+        let inner = self.inner.clone();
+        futures::stream::unfold((states_rx, inner), move |(mut states_rx, inner)| {
+            let lease_name = lease_name.clone();
+            async move {
+                loop {
+                    match states_rx.recv().await {
+                        Ok(lease_state) if lease_state.lease_name() == lease_name => {
+                            return Some((lease_state, (states_rx, inner)))
+                        }
+                        Ok(_) => {}
+                        Err(broadcast::error::RecvError::Lagged(missed)) => {
+                            log::warn!("{}.bulk_watch() => lagged by {}", &lease_name, missed)
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,68 @@
+use crate::lease::LeaseState;
+use crate::task::spawn_named;
+use futures::future::{AbortHandle, Abortable};
+use futures::{Stream, StreamExt};
+use once_cell::sync::OnceCell;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Latest state of a lease as seen by a background watch, shared by clones of a lock.
+/// The watch stops once the cache is dropped.
+#[derive(Default)]
+pub(crate) struct LeaseCache {
+    latest: Mutex<Option<(LeaseState, Instant)>>,
+    watch: OnceCell<AbortHandle>,
+}
+
+impl LeaseCache {
+    /// Start the watch updating the cache, unless it runs already.
+    pub(crate) fn start<S, F>(self: &Arc<Self>, lease_name: &str, watch: F)
+    where
+        S: Stream<Item = LeaseState> + Send + 'static,
+        F: FnOnce() -> S,
+    {
+        self.watch.get_or_init(|| {
+            let (abort_handle, abort_reg) = AbortHandle::new_pair();
+            let cache = Arc::downgrade(self);
+            let mut states = Box::pin(watch());
+            spawn_named(
+                &format!("lease-watch:{}", lease_name),
+                None,
+                Abortable::new(
+                    async move {
+                        while let Some(lease_state) = states.next().await {
+                            match cache.upgrade() {
+                                Some(cache) => cache.put(lease_state),
+                                None => return,
+                            }
+                        }
+                    },
+                    abort_reg,
+                ),
+            );
+            abort_handle
+        });
+    }
+
+    /// Latest state, if observed within `max_staleness`.
+    pub(crate) fn get(&self, max_staleness: Duration) -> Option<LeaseState> {
+        match &*self.latest.lock().unwrap() {
+            Some((lease_state, observed)) if observed.elapsed() <= max_staleness => {
+                Some(lease_state.clone())
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn put(&self, lease_state: LeaseState) {
+        *self.latest.lock().unwrap() = Some((lease_state, Instant::now()));
+    }
+}
+
+impl Drop for LeaseCache {
+    fn drop(&mut self) {
+        if let Some(watch) = self.watch.get() {
+            watch.abort();
+        }
+    }
+}
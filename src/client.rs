@@ -0,0 +1,60 @@
+use crate::lease::{Error, LeaseLock, LeaseLockClient, LeaseState};
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+
+/// Low-level operations on a lease, for composing custom election loops while reusing the
+/// patches of [LeaseLock]. Each call is a single attempt: nothing is retried, renewed or
+/// released in the background. Writes are conditional on the resource version of the given
+/// state, so acting on a stale state fails with a conflict.
+#[derive(Clone)]
+pub struct LeaseClient {
+    client: LeaseLockClient,
+}
+
+impl LeaseClient {
+    /// Client with default configuration; see [LeaseLock::lease_client] for a configured one.
+    pub fn new(api: kube::Api<LeaseObject>, lease_name: String) -> Self {
+        LeaseLock::new(api, lease_name).lease_client()
+    }
+
+    pub(crate) fn from_client(client: LeaseLockClient) -> Self {
+        Self { client }
+    }
+
+    /// Current state of the lease.
+    pub async fn get_state(&self) -> Result<LeaseState, Error> {
+        self.client.get_state().await
+    }
+
+    /// Take over the lease for `holder_id` if it has no live holder (or is held by `holder_id`
+    /// already). Return the new state if we hold the lease, None if somebody else does.
+    pub async fn try_acquire_once(&self, holder_id: &str) -> Result<Option<LeaseState>, Error> {
+        let identity = self.client.holder_identity(holder_id);
+        let lease_state = self.client.get_state().await?;
+        if matches!(lease_state.owner(), Some(owner) if owner != identity) {
+            return Ok(None);
+        }
+        let lease_state = self.take_over(holder_id, &lease_state).await?;
+        Ok(Some(lease_state).filter(|s| s.owner() == Some(identity.as_str())))
+    }
+
+    /// Take over the lease as of `lease_state` for `holder_id`, without checking who holds it.
+    pub async fn take_over(
+        &self,
+        holder_id: &str,
+        lease_state: &LeaseState,
+    ) -> Result<LeaseState, Error> {
+        let identity = self.client.holder_identity(holder_id);
+        self.client.overwrite_once(&identity, lease_state).await
+    }
+
+    /// Extend the lease held as of `lease_state`.
+    pub async fn renew(&self, lease_state: LeaseState) -> Result<LeaseState, Error> {
+        self.client.renew_lease(lease_state, true).await
+    }
+
+    /// Release the lease held as of `lease_state`. Unlike a [LeaseGuard](crate::LeaseGuard)
+    /// release, the lease isn't re-read: a stale `lease_state` fails with a conflict.
+    pub async fn release(&self, lease_state: &LeaseState) -> Result<LeaseState, Error> {
+        self.client.release_patch(lease_state, None).await
+    }
+}
@@ -0,0 +1,57 @@
+use crate::lease::{Error, LeaseGuard, LeaseLock};
+use std::time::Duration;
+
+/// Namespace of cluster-wide locks unless configured otherwise.
+pub const DEFAULT_CLUSTER_NAMESPACE: &str = "kube-system";
+
+/// Prefix of lease names of cluster-wide locks, keeping them apart from namespace-scoped ones.
+const CLUSTER_PREFIX: &str = "cluster-";
+
+/// Cluster-wide singleton lock. Leases are namespaced, so the lease lives in a well-known
+/// namespace (`kube-system` by default), named `cluster-<name>`, regardless of the namespace
+/// of the process.
+pub struct ClusterLock {
+    lease_lock: LeaseLock,
+    lock_name: String,
+}
+
+impl ClusterLock {
+    pub fn new(client: kube::Client, name: &str) -> Self {
+        Self::in_namespace(client, DEFAULT_CLUSTER_NAMESPACE, name)
+    }
+
+    /// Cluster-wide lock kept in `namespace`; all users of the lock must agree on it.
+    pub fn in_namespace(client: kube::Client, namespace: &str, name: &str) -> Self {
+        let api = kube::Api::namespaced(client, namespace);
+        Self {
+            lease_lock: LeaseLock::new(api, format!("{}{}", CLUSTER_PREFIX, name))
+                .with_create_if_missing(true),
+            lock_name: name.to_string(),
+        }
+    }
+
+    /// Configure the underlying [LeaseLock], e.g. `|l| l.with_lease_duration_sec(30)`.
+    pub fn configure(mut self, configure: impl FnOnce(LeaseLock) -> LeaseLock) -> Self {
+        self.lease_lock = configure(self.lease_lock);
+        self
+    }
+
+    /// Name of the lock, without the prefix of the lease name.
+    pub fn lock_name(&self) -> &str {
+        &self.lock_name
+    }
+
+    /// Acquire the lock like [LeaseLock::acquire], creating the lease if missing.
+    pub async fn acquire(
+        &self,
+        holder_id: &str,
+        acquire_timeout: Option<Duration>,
+    ) -> Result<LeaseGuard, Error> {
+        self.lease_lock.acquire(holder_id, acquire_timeout).await
+    }
+
+    /// See [LeaseLock::complete_all_operations].
+    pub async fn complete_all_operations(&self) {
+        self.lease_lock.complete_all_operations().await;
+    }
+}
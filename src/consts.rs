@@ -0,0 +1,68 @@
+//! Keys of annotations and labels the crate writes to leases, and the default field manager.
+//! These are stable across versions, so external tooling (dashboards, policies) may rely on them.
+//! A value format that changes is tracked by [SCHEMA_VERSION_ANNOTATION] instead.
+
+/// Field manager of server-side apply patches, unless changed by [crate::defaults].
+pub const DEFAULT_FIELD_MANAGER: &str = "lease-rs";
+
+/// Prefix of all annotation and label keys below.
+pub const KEY_PREFIX: &str = "rust-kube-lease/";
+
+/// Annotation with the schema version the crate annotations of a lease were written in.
+pub const SCHEMA_VERSION_ANNOTATION: &str = "rust-kube-lease/schema-version";
+
+/// Annotation holding the time of the holder's last heartbeat, see
+/// [LeaseLock::with_heartbeat](crate::LeaseLock::with_heartbeat).
+pub const HEARTBEAT_ANNOTATION: &str = "rust-kube-lease/heartbeat";
+
+/// Annotation naming the holder whose expired lease was taken over.
+pub const PREVIOUS_HOLDER_ANNOTATION: &str = "rust-kube-lease/previous-holder";
+
+/// Annotation holding the time of the takeover of an expired lease.
+pub const TAKEN_OVER_AT_ANNOTATION: &str = "rust-kube-lease/taken-over-at";
+
+/// Annotation holding co-holders of a shared lease and their renew times.
+pub const CO_HOLDERS_ANNOTATION: &str = "rust-kube-lease/co-holders";
+
+/// Annotation with the epoch of a restart orchestrated by the leader.
+pub const RESTART_EPOCH_ANNOTATION: &str = "rust-kube-lease/restart-epoch";
+
+/// Annotation with `<epoch>/<holder>` of a successor ready to take over for that restart epoch.
+pub const READY_SUCCESSOR_ANNOTATION: &str = "rust-kube-lease/ready-successor";
+
+/// Human-readable holder of the lease, see
+/// [LeaseLock::with_status_annotations](crate::LeaseLock::with_status_annotations).
+pub const HELD_BY_ANNOTATION: &str = "rust-kube-lease/held-by";
+
+/// Human-readable expiry time of the lease unless renewed.
+pub const EXPIRES_AT_ANNOTATION: &str = "rust-kube-lease/expires-at";
+
+/// Number of renewals by the current holder since it acquired the lease.
+pub const RENEWALS_ANNOTATION: &str = "rust-kube-lease/renewals";
+
+/// Failure domain (e.g. zone) of the holder, or of the last holder of a released lease, see
+/// [LeaseLock::with_zone](crate::LeaseLock::with_zone).
+pub const HOLDER_ZONE_ANNOTATION: &str = "rust-kube-lease/holder-zone";
+
+/// Holder that announced its intent to acquire the lease, see
+/// [LeaseLock::prepare_acquire](crate::LeaseLock::prepare_acquire).
+pub const PENDING_HOLDER_ANNOTATION: &str = "rust-kube-lease/pending-holder";
+
+/// Fencing token of the lease holder that last wrote an object, see
+/// [LeaseLock::update_guarded](crate::LeaseLock::update_guarded).
+pub const FENCING_TOKEN_ANNOTATION: &str = "rust-kube-lease/fencing-token";
+
+/// Payload left by the last holder for the next one, see
+/// [LeaseGuard::release_with_handover](crate::LeaseGuard::release_with_handover).
+pub const HANDOVER_ANNOTATION: &str = "rust-kube-lease/handover";
+
+/// Annotation marking a schedule window of a [JobGuard](crate::JobGuard) as done.
+pub const COMPLETED_AT_ANNOTATION: &str = "rust-kube-lease/completed-at";
+
+/// Label with the job name on window leases of a [JobGuard](crate::JobGuard),
+/// for cleaning up old windows by selector.
+pub const JOB_LABEL: &str = "rust-kube-lease/job";
+
+/// Annotation holding the leader election record of ConfigMap and Endpoints locks, in
+/// client-go's format, see [ResourceLock](crate::ResourceLock).
+pub const LEADER_ANNOTATION: &str = "control-plane.alpha.kubernetes.io/leader";
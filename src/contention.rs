@@ -0,0 +1,85 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Window over which conflicts count towards [ContentionLevel::conflicts].
+const CONFLICT_WINDOW: Duration = Duration::from_secs(60);
+
+/// How hot a lease is, as seen from this process.
+/// See [LeaseLock::contention_level](crate::LeaseLock::contention_level).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ContentionLevel {
+    /// Takeover attempts lost to another writer during the last minute.
+    pub conflicts: usize,
+    /// Acquires of this lock currently in progress in this process.
+    pub waiters: usize,
+}
+
+/// Priority of an acquire over other acquires of the same lock in this process.
+/// See [LeaseLock::acquire_with_priority](crate::LeaseLock::acquire_with_priority).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Default)]
+pub(crate) struct Contention {
+    /// Waiters by [Priority].
+    waiters: [AtomicUsize; 3],
+    conflicts: Mutex<VecDeque<Instant>>,
+}
+
+/// Counts as a waiter until dropped.
+pub(crate) struct Waiter<'a>(&'a Contention, Priority);
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        self.0.waiters[self.1 as usize].fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Contention {
+    pub(crate) fn enter(&self, priority: Priority) -> Waiter<'_> {
+        self.waiters[priority as usize].fetch_add(1, Ordering::Relaxed);
+        Waiter(self, priority)
+    }
+
+    /// Whether a waiter of higher priority than `priority` is waiting.
+    pub(crate) fn outranked(&self, priority: Priority) -> bool {
+        self.waiters[priority as usize + 1..]
+            .iter()
+            .any(|waiters| waiters.load(Ordering::Relaxed) > 0)
+    }
+
+    pub(crate) fn conflict(&self) {
+        let mut conflicts = self.conflicts.lock().unwrap();
+        conflicts.push_back(Instant::now());
+        Self::expire(&mut conflicts);
+    }
+
+    pub(crate) fn level(&self) -> ContentionLevel {
+        let mut conflicts = self.conflicts.lock().unwrap();
+        Self::expire(&mut conflicts);
+        ContentionLevel {
+            conflicts: conflicts.len(),
+            waiters: self
+                .waiters
+                .iter()
+                .map(|waiters| waiters.load(Ordering::Relaxed))
+                .sum(),
+        }
+    }
+
+    fn expire(conflicts: &mut VecDeque<Instant>) {
+        while let Some(t) = conflicts.front() {
+            if t.elapsed() < CONFLICT_WINDOW {
+                break;
+            }
+            conflicts.pop_front();
+        }
+    }
+}
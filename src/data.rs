@@ -0,0 +1,43 @@
+use crate::lease::LeaseGuard;
+use std::ops::{Deref, DerefMut};
+
+/// [LeaseGuard] bundled with user data, see [LeaseGuard::with_data]. On drop, the data is
+/// dropped first, and only then the lease release is scheduled, so application cleanup in the
+/// data's Drop still runs under the lock.
+pub struct DataGuard<T> {
+    // Fields drop in declaration order: data before guard.
+    data: T,
+    guard: LeaseGuard,
+}
+
+impl<T> DataGuard<T> {
+    pub fn guard(&self) -> &LeaseGuard {
+        &self.guard
+    }
+
+    /// Split into the data and the guard, leaving their drop order to the caller.
+    pub fn into_parts(self) -> (T, LeaseGuard) {
+        (self.data, self.guard)
+    }
+}
+
+impl LeaseGuard {
+    /// Bundle `data` with the guard, so that it is dropped before the lease is released.
+    pub fn with_data<T>(self, data: T) -> DataGuard<T> {
+        DataGuard { data, guard: self }
+    }
+}
+
+impl<T> Deref for DataGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+
+impl<T> DerefMut for DataGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+}
@@ -0,0 +1,77 @@
+use crate::consts::DEFAULT_FIELD_MANAGER;
+use once_cell::sync::OnceCell;
+use std::time::Duration;
+use tokio_retry::strategy::ExponentialBackoff;
+
+static DEFAULTS: OnceCell<Defaults> = OnceCell::new();
+
+/// Process-wide defaults inherited by every [LeaseLock](crate::LeaseLock) created after they
+/// are installed, unless overridden on the lock itself.
+/// There is no metrics registry to default: lease metrics are always recorded process-wide and
+/// exported with [render_textfile](crate::metrics::render_textfile), so there is nothing to
+/// choose per process.
+#[derive(Clone, Debug)]
+pub struct Defaults {
+    pub(crate) lease_duration: Duration,
+    pub(crate) expo: ExponentialBackoff,
+    pub(crate) field_manager: String,
+}
+
+impl Default for Defaults {
+    fn default() -> Self {
+        Self {
+            lease_duration: Duration::from_secs(10),
+            expo: ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1)),
+            field_manager: DEFAULT_FIELD_MANAGER.into(),
+        }
+    }
+}
+
+/// Start configuring process-wide defaults; finish with [Defaults::install].
+///
+/// ```no_run
+/// rust_kube_lease::defaults()
+///     .with_lease_duration_sec(30)
+///     .with_field_manager("my-operator")
+///     .install()
+///     .unwrap();
+/// ```
+pub fn defaults() -> Defaults {
+    Defaults::default()
+}
+
+impl Defaults {
+    /// See [LeaseLock::with_lease_duration_sec](crate::LeaseLock::with_lease_duration_sec).
+    pub fn with_lease_duration_sec(mut self, sec: i32) -> Self {
+        self.lease_duration = Duration::from_secs(sec.max(0) as u64);
+        self
+    }
+
+    /// See [LeaseLock::with_lease_duration](crate::LeaseLock::with_lease_duration).
+    pub fn with_lease_duration(mut self, duration: Duration) -> Self {
+        self.lease_duration = duration;
+        self
+    }
+
+    /// See [LeaseLock::with_expo_backoff](crate::LeaseLock::with_expo_backoff).
+    pub fn with_expo_backoff(mut self, expo: ExponentialBackoff) -> Self {
+        self.expo = expo;
+        self
+    }
+
+    /// See [LeaseLock::with_field_manager](crate::LeaseLock::with_field_manager).
+    pub fn with_field_manager(mut self, field_manager: impl Into<String>) -> Self {
+        self.field_manager = field_manager.into();
+        self
+    }
+
+    /// Install as process-wide defaults. Defaults can be installed only once;
+    /// subsequent attempts hand the rejected value back.
+    pub fn install(self) -> Result<(), Self> {
+        DEFAULTS.set(self)
+    }
+}
+
+pub(crate) fn current() -> Defaults {
+    DEFAULTS.get().cloned().unwrap_or_default()
+}
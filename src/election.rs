@@ -0,0 +1,232 @@
+use crate::lease::{LeaseLock, LeaseState};
+use crate::metrics;
+use crate::role::Role;
+use futures::future::{BoxFuture, Either};
+use futures::{FutureExt, StreamExt};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+type StartedLeading = Arc<dyn Fn(LeaseState) -> BoxFuture<'static, ()> + Send + Sync>;
+
+type StoppedLeading = Arc<dyn Fn() + Send + Sync>;
+
+type NewLeader = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Continuous campaign for a lease with lifecycle callbacks, after client-go's leaderelection:
+/// acquire the lease, lead until ownership is lost, and campaign again.
+pub struct LeaderElector {
+    lease_lock: LeaseLock,
+    holder_id: String,
+    retry_period: Duration,
+    on_started_leading: Option<StartedLeading>,
+    on_stopped_leading: Option<StoppedLeading>,
+    on_new_leader: Option<NewLeader>,
+    role_tx: watch::Sender<Role>,
+    enabled: watch::Sender<bool>,
+}
+
+/// Calls `on_stopped_leading` when leadership ends, including when [LeaderElector::run] is
+/// dropped while leading.
+struct Leading<'a>(&'a Option<StoppedLeading>);
+
+impl Drop for Leading<'_> {
+    fn drop(&mut self) {
+        if let Some(stopped) = self.0 {
+            stopped();
+        }
+    }
+}
+
+impl LeaderElector {
+    pub fn new(lease_lock: LeaseLock, holder_id: impl Into<String>) -> Self {
+        Self {
+            lease_lock,
+            holder_id: holder_id.into(),
+            retry_period: Duration::from_secs(2),
+            on_started_leading: None,
+            on_stopped_leading: None,
+            on_new_leader: None,
+            role_tx: watch::channel(Role::Candidate).0,
+            enabled: watch::channel(true).0,
+        }
+    }
+
+    /// Wait before campaigning again after acquire failed. Default is 2 seconds.
+    pub fn with_retry_period(mut self, period: Duration) -> Self {
+        self.retry_period = period;
+        self
+    }
+
+    /// Run the future returned by `callback` whenever leadership is taken. It is aborted as
+    /// soon as leadership is lost, see [crate::LeaseGuard::spawn_scoped].
+    pub fn on_started_leading<F, Fut>(mut self, callback: F) -> Self
+    where
+        F: Fn(LeaseState) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_started_leading = Some(Arc::new(move |lease_state| callback(lease_state).boxed()));
+        self
+    }
+
+    /// Call `callback` whenever leadership ends, by loss or because the election stopped.
+    pub fn on_stopped_leading<F>(mut self, callback: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.on_stopped_leading = Some(Arc::new(callback));
+        self
+    }
+
+    /// Call `callback` with the identity of the leader whenever it changes, this process included.
+    pub fn on_new_leader<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.on_new_leader = Some(Arc::new(callback));
+        self
+    }
+
+    pub fn lease_lock(&self) -> &LeaseLock {
+        &self.lease_lock
+    }
+
+    /// Role of this process in the election, updated as it changes.
+    pub fn role(&self) -> watch::Receiver<Role> {
+        self.role_tx.subscribe()
+    }
+
+    /// Take this process out of the election, e.g. to drain a replica for maintenance without
+    /// restarting it, or put it back. If leading when disabled, it steps down: scoped tasks are
+    /// aborted and the lease is released right away, so another replica can take over without
+    /// waiting for expiry. While disabled, the role is [Role::Standby].
+    pub fn set_enabled(&self, enabled: bool) {
+        log::info!(
+            "{}.set_enabled({}, {})",
+            self.lease_lock.name(),
+            &self.holder_id,
+            enabled
+        );
+        self.enabled.send_replace(enabled);
+    }
+
+    /// Whether this process takes part in the election, see [LeaderElector::set_enabled].
+    pub fn is_enabled(&self) -> bool {
+        *self.enabled.borrow()
+    }
+
+    /// Resolve once [LeaderElector::is_enabled] is `value`.
+    async fn until_enabled(&self, value: bool) {
+        let mut enabled = self.enabled.subscribe();
+        // the sender lives as long as self, so changed() doesn't fail meanwhile
+        while *enabled.borrow_and_update() != value && enabled.changed().await.is_ok() {}
+    }
+
+    fn set_role(&self, role: Role) {
+        if *self.role_tx.borrow() == role {
+            return;
+        }
+        metrics::record(self.lease_lock.name(), |m| m.role = Some(role.label()));
+        self.role_tx.send_replace(role);
+    }
+
+    /// Campaign forever. Drop the future to stop: the lease is released if held.
+    pub async fn run(&self) {
+        let mut changes = self.lease_lock.observe().changes().boxed();
+        let identity = self.lease_lock.client().holder_identity(&self.holder_id);
+        let mut leader: Option<String> = None;
+        let campaign = self.campaign();
+        tokio::pin!(campaign);
+        loop {
+            tokio::select! {
+                _ = &mut campaign => return,
+                Some(lease_state) = changes.next() => {
+                    if self.is_enabled() {
+                        self.set_role(Role::of(&lease_state, &identity));
+                    }
+                    let owner = lease_state.owner().map(str::to_string);
+                    if owner != leader {
+                        if let (Some(owner), Some(callback)) = (&owner, &self.on_new_leader) {
+                            callback(owner);
+                        }
+                        leader = owner;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn campaign(&self) {
+        let identity = self.lease_lock.client().holder_identity(&self.holder_id);
+        loop {
+            if !self.is_enabled() {
+                log::info!(
+                    "{}.campaign({}) => standby",
+                    self.lease_lock.name(),
+                    &self.holder_id
+                );
+                self.set_role(Role::Standby);
+                self.until_enabled(true).await;
+                self.set_role(Role::Candidate);
+                continue;
+            }
+            let guard = match self
+                .lease_lock
+                .leadership_or(&self.holder_id, self.until_enabled(false))
+                .await
+            {
+                Ok(Either::Left(guard)) => guard,
+                Ok(Either::Right(())) => continue,
+                Err(e) => {
+                    self.set_role(Role::Offline);
+                    log::error!(
+                        "{}.campaign({}) => {}",
+                        self.lease_lock.name(),
+                        &self.holder_id,
+                        e
+                    );
+                    tokio::time::sleep(self.retry_period).await;
+                    continue;
+                }
+            };
+            log::info!(
+                "{}.campaign({}) => leading",
+                self.lease_lock.name(),
+                &self.holder_id
+            );
+            self.set_role(Role::of(guard.lease_state(), &identity));
+            let leading = Leading(&self.on_stopped_leading);
+            if let Some(started) = &self.on_started_leading {
+                guard.spawn_scoped(started(guard.lease_state().clone()));
+            }
+            let disabled = tokio::select! {
+                _ = guard.lost() => false,
+                _ = self.until_enabled(false) => true,
+            };
+            if disabled {
+                log::info!(
+                    "{}.campaign({}) => disabled; step down",
+                    self.lease_lock.name(),
+                    &self.holder_id
+                );
+                if let Err(e) = guard.release().await {
+                    log::error!(
+                        "{}.campaign({}) => {}",
+                        self.lease_lock.name(),
+                        &self.holder_id,
+                        e
+                    );
+                }
+            } else {
+                log::warn!(
+                    "{}.campaign({}) => stopped leading",
+                    self.lease_lock.name(),
+                    &self.holder_id
+                );
+                drop(guard);
+            }
+            drop(leading);
+        }
+    }
+}
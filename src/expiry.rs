@@ -0,0 +1,72 @@
+use crate::defaults;
+use crate::lease::LeaseState;
+use crate::watch::watch_lease;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use std::time::Duration;
+
+/// Crossing of a lease between live and expired, see [expiry_stream].
+#[derive(Clone, Debug)]
+pub enum Expiry {
+    /// The holder stopped renewing and the lease expired.
+    Expired(LeaseState),
+    /// The expired lease is live again: renewed late, or taken over.
+    Live(LeaseState),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Free,
+    Live,
+    Expired,
+}
+
+fn phase(lease_state: &LeaseState) -> Phase {
+    match (lease_state.holder(), lease_state.owner()) {
+        (None, _) => Phase::Free,
+        (Some(_), Some(_)) => Phase::Live,
+        (Some(_), None) => Phase::Expired,
+    }
+}
+
+/// Endless stream of the crossings of lease `lease_name` from live to expired and back, as
+/// observed by a watch, without ever writing to it. Meant for alerting when some other
+/// component's leader stops renewing. Releases aren't expiries and aren't reported.
+pub fn expiry_stream(
+    api: kube::Api<LeaseObject>,
+    lease_name: &str,
+) -> impl Stream<Item = Expiry> + Send + 'static {
+    let expo = defaults::current().expo;
+    let states: BoxStream<'static, LeaseState> =
+        watch_lease(api, lease_name.to_string(), expo).boxed();
+    stream::unfold(
+        (states, None::<LeaseState>, Phase::Free),
+        |(mut states, mut last, mut last_phase)| async move {
+            loop {
+                let expires_at = last
+                    .as_ref()
+                    .filter(|_| last_phase == Phase::Live)
+                    .map(|lease_state| lease_state.remaining());
+                let sleep = expires_at.unwrap_or(Duration::ZERO);
+                tokio::select! {
+                    lease_state = states.next() => match lease_state {
+                        Some(lease_state) => last = Some(lease_state),
+                        None => return None,
+                    },
+                    _ = tokio::time::sleep(sleep), if expires_at.is_some() => {}
+                }
+                let lease_state = last.clone().expect("observed");
+                let phase = phase(&lease_state);
+                let crossing = match (last_phase, phase) {
+                    (Phase::Live, Phase::Expired) => Some(Expiry::Expired(lease_state)),
+                    (Phase::Expired, Phase::Live) => Some(Expiry::Live(lease_state)),
+                    _ => None,
+                };
+                last_phase = phase;
+                if let Some(crossing) = crossing {
+                    return Some((crossing, (states, last, last_phase)));
+                }
+            }
+        },
+    )
+}
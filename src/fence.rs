@@ -0,0 +1,56 @@
+use crate::lease::{Error, LeaseState};
+use futures::future::BoxFuture;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A resource in another system (e.g. a Postgres advisory lock) whose lifetime is bound to the
+/// lease: acquired right after the lease, released before the lease is released or once it
+/// is lost. See [LeaseLock::with_fenced_resource](crate::LeaseLock::with_fenced_resource).
+pub trait FencedResource: Send + Sync {
+    /// Acquire the resource on behalf of the new holder of the lease.
+    fn acquire<'a>(&'a self, lease_state: &'a LeaseState) -> BoxFuture<'a, Result<(), BoxError>>;
+
+    /// Release the resource.
+    fn release(&self) -> BoxFuture<'_, ()>;
+}
+
+/// Fenced resources of a single guard.
+pub(crate) struct Fence {
+    resources: Vec<Arc<dyn FencedResource>>,
+    /// Number of leading `resources` currently acquired.
+    held: Mutex<usize>,
+}
+
+impl Fence {
+    pub(crate) fn new(resources: Vec<Arc<dyn FencedResource>>) -> Self {
+        Self {
+            resources,
+            held: Mutex::new(0),
+        }
+    }
+
+    /// Acquire all resources in order. On failure, release the ones already acquired.
+    pub(crate) async fn acquire_all(&self, lease_state: &LeaseState) -> Result<(), Error> {
+        let mut held = self.held.lock().await;
+        while *held < self.resources.len() {
+            if let Err(e) = self.resources[*held].acquire(lease_state).await {
+                drop(held);
+                self.release_all().await;
+                return Err(Error::FencedResource(e));
+            }
+            *held += 1;
+        }
+        Ok(())
+    }
+
+    /// Release acquired resources in reverse order. Safe to call more than once.
+    pub(crate) async fn release_all(&self) {
+        let mut held = self.held.lock().await;
+        while *held > 0 {
+            self.resources[*held - 1].release().await;
+            *held -= 1;
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::consts::FENCING_TOKEN_ANNOTATION;
+use crate::lease::{Error, LeaseLock};
+use http::StatusCode;
+use kube::api::PostParams;
+use kube::ResourceExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+use std::time::Duration;
+
+impl LeaseLock {
+    /// Acquire the lock, read-modify-write object `name` of `obj_api` with `f` under it, and
+    /// release the lock. The object is stamped with the fencing token of the acquisition (see
+    /// [LeaseGuard::fencing_token](crate::LeaseGuard::fencing_token)), and the write is refused
+    /// with [Error::Fenced] if the object was already written under a newer token, i.e. by a later
+    /// holder. Writes conflicting with concurrent updates of the object are retried with a fresh
+    /// read.
+    pub async fn update_guarded<K, F>(
+        &self,
+        holder_id: &str,
+        acquire_timeout: Option<Duration>,
+        obj_api: &kube::Api<K>,
+        name: &str,
+        mut f: F,
+    ) -> Result<K, Error>
+    where
+        K: kube::Resource + Clone + DeserializeOwned + Serialize + Debug,
+        F: FnMut(&mut K),
+    {
+        let guard = self.acquire(holder_id, acquire_timeout).await?;
+        let token = guard.fencing_token();
+        loop {
+            let mut obj = obj_api.get(name).await?;
+            let seen = obj
+                .annotations()
+                .get(FENCING_TOKEN_ANNOTATION)
+                .and_then(|seen| seen.parse().ok());
+            if let Some(seen) = seen.filter(|seen| *seen > token) {
+                return Err(Error::Fenced { token, seen });
+            }
+            f(&mut obj);
+            obj.annotations_mut()
+                .insert(FENCING_TOKEN_ANNOTATION.into(), token.to_string());
+            if !guard.is_held() {
+                return Err(Error::Lost);
+            }
+            // replace carries the resourceVersion read above, so it fails on concurrent updates
+            match obj_api.replace(name, &PostParams::default(), &obj).await {
+                Ok(obj) => return Ok(obj),
+                Err(kube::Error::Api(api_err)) if api_err.code == StatusCode::CONFLICT => {
+                    log::debug!(
+                        "{}.update_guarded({}) => conflict; retry",
+                        guard.lease_state().lease_name(),
+                        name
+                    );
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::consts::{COMPLETED_AT_ANNOTATION, JOB_LABEL};
+use crate::lease::{Error, LeaseGuard, LeaseLock, LeaseState};
+use http::StatusCode;
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use kube::api::{DeleteParams, ListParams, Preconditions};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// Windows kept before the current one; leases of older windows are deleted by
+/// [JobGuard::claim].
+const RETAINED_WINDOWS: i64 = 3;
+
+/// Dedup guard for CronJob runs: at most one pod runs a job per schedule window, even when
+/// concurrencyPolicy gaps or retries start several. Each window gets its own lease, named
+/// after the job and the start of the window, and labeled with the job name.
+pub struct JobGuard {
+    lease_lock: LeaseLock,
+    guard: LeaseGuard,
+}
+
+impl JobGuard {
+    /// Claim the current window of length `period` for `job_name`. Return None if another pod
+    /// is running the window or it has been completed already. Leases of windows that started
+    /// more than 3 periods ago and aren't held are deleted along the way.
+    pub async fn claim(
+        api: kube::Api<LeaseObject>,
+        job_name: &str,
+        period: Duration,
+        holder_id: &str,
+    ) -> Result<Option<JobGuard>, Error> {
+        let period = period.as_secs() as i64;
+        if period == 0 {
+            return Err(Error::Config("job window shorter than a second".into()));
+        }
+        let now = chrono::Utc::now().timestamp();
+        let window = now - now.rem_euclid(period);
+        let lease_name = format!("{}-{}", job_name, window);
+        let lease_lock = LeaseLock::new(api.clone(), lease_name);
+        let labels = BTreeMap::from([(JOB_LABEL.to_string(), job_name.to_string())]);
+        lease_lock.client().create_if_missing(&labels).await?;
+        if let Err(e) = prune(&api, job_name, window - RETAINED_WINDOWS * period).await {
+            log::error!("{}.prune() => {}", job_name, e);
+        }
+
+        let guard = match lease_lock.try_acquire(holder_id).await? {
+            Some(guard) => guard,
+            None => {
+                log::info!(
+                    "{}: claimed by another pod",
+                    lease_lock.client().lease_name()
+                );
+                return Ok(None);
+            }
+        };
+        let completed_at = lease_lock
+            .client()
+            .get_state()
+            .await?
+            .annotations()
+            .get(COMPLETED_AT_ANNOTATION)
+            .cloned();
+        if let Some(completed_at) = completed_at {
+            log::info!(
+                "{}: completed at {}",
+                lease_lock.client().lease_name(),
+                completed_at
+            );
+            return Ok(None);
+        }
+        Ok(Some(JobGuard { lease_lock, guard }))
+    }
+
+    /// Record the window as completed, so no other pod runs it, and release the lease.
+    /// Dropping the guard without completing releases the window for a retry instead.
+    pub async fn complete(self) -> Result<(), Error> {
+        let JobGuard { lease_lock, guard } = self;
+        let now = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let completed = lease_lock
+            .client()
+            .annotate(COMPLETED_AT_ANNOTATION, Some(&now))
+            .await;
+        drop(guard);
+        lease_lock.complete_all_operations().await;
+        completed.map(|_| ())
+    }
+}
+
+/// Delete the leases of windows of `job_name` started before `before`, unless held: a pod may
+/// still be running an old window.
+async fn prune(api: &kube::Api<LeaseObject>, job_name: &str, before: i64) -> Result<(), Error> {
+    let list_params = ListParams::default().labels(&format!("{}={}", JOB_LABEL, job_name));
+    for lease_obj in api.list(&list_params).await? {
+        let lease_state = LeaseState::try_from(lease_obj)?;
+        let started = lease_state
+            .lease_name()
+            .strip_prefix(job_name)
+            .and_then(|suffix| suffix.strip_prefix('-'))
+            .and_then(|start| start.parse::<i64>().ok());
+        if !matches!(started, Some(started) if started < before) || lease_state.owner().is_some() {
+            continue;
+        }
+        // unless changed since listed, e.g. claimed by a late pod
+        let params = DeleteParams::default().preconditions(Preconditions {
+            resource_version: Some(lease_state.resource_version().to_string()),
+            uid: None,
+        });
+        match api.delete(lease_state.lease_name(), &params).await {
+            Ok(_) => log::debug!("{}.prune() => deleted", lease_state.lease_name()),
+            Err(kube::Error::Api(api_err))
+                if api_err.code == StatusCode::CONFLICT
+                    || api_err.code == StatusCode::NOT_FOUND => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::lease::{Error, LeaseGuard, LeaseLock};
+use futures::future::Either;
+use http::StatusCode;
+use std::future::Future;
+
+/// Leadership is not currently held, see [require_leadership].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("not the leader of lease {lease_name}")]
+pub struct NotLeader {
+    pub lease_name: String,
+}
+
+impl NotLeader {
+    /// HTTP status to answer leader-only requests with: 503, so that clients retry elsewhere.
+    pub fn status_code(&self) -> StatusCode {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+/// Check at the top of leader-only request handlers that `guard` still holds the lease and that it
+/// hasn't expired while renewals were failing. Costs an atomic read; see [LeaseGuard::is_valid].
+pub fn require_leadership(guard: &LeaseGuard) -> Result<(), NotLeader> {
+    if guard.is_valid() {
+        Ok(())
+    } else {
+        Err(NotLeader {
+            lease_name: guard.lease_state().lease_name().to_string(),
+        })
+    }
+}
+
+impl LeaseLock {
+    /// Campaign for the lease as `holder_id` until it is acquired or `other` completes,
+    /// whichever comes first, e.g. to give up campaigning on a shutdown signal. Once `other`
+    /// completes, the acquisition is abandoned. Should that happen while the acquiring write is in
+    /// flight, the lease may be left held with no guard to release it: it then expires after the
+    /// lease duration, as if this process had crashed.
+    pub async fn leadership_or<F>(
+        &self,
+        holder_id: &str,
+        other: F,
+    ) -> Result<Either<LeaseGuard, F::Output>, Error>
+    where
+        F: Future,
+    {
+        tokio::select! {
+            acquired = self.acquire(holder_id, None) => acquired.map(Either::Left),
+            output = other => Ok(Either::Right(output)),
+        }
+    }
+}
@@ -1,22 +1,131 @@
-use futures::future::{AbortHandle, Abortable};
+use crate::bulk::BulkWatch;
+use crate::cache::LeaseCache;
+use crate::client::LeaseClient;
+use crate::consts::{
+    EXPIRES_AT_ANNOTATION, HANDOVER_ANNOTATION, HEARTBEAT_ANNOTATION, HELD_BY_ANNOTATION,
+    HOLDER_ZONE_ANNOTATION, PENDING_HOLDER_ANNOTATION, PREVIOUS_HOLDER_ANNOTATION,
+    RENEWALS_ANNOTATION, TAKEN_OVER_AT_ANNOTATION,
+};
+use crate::contention::{Contention, ContentionLevel, Priority};
+use crate::defaults;
+use crate::fence::{BoxError, Fence, FencedResource};
+#[cfg(feature = "debug-lock-order")]
+use crate::lock_order;
+use crate::metrics;
+use crate::observe::LeaseObserver;
+#[cfg(feature = "otel")]
+use crate::otel;
+use crate::persist::StateFile;
+use crate::random::{RandomSource, ThreadRandom};
+use crate::registry::{self, HeldInfo, Registration};
+use crate::retry::{Operation, RetryDecision, RetryPolicy};
+use crate::schema::{self, Annotations, SCHEMA_VERSION};
+use crate::status::ConditionReporter;
+use crate::task::{spawn_named, Completion};
+use crate::token::GuardToken;
+use crate::tuning::RenewalStats;
+use crate::watch::watch_lease;
+use futures::future::{AbortHandle, Abortable, BoxFuture};
+use futures::{stream, FutureExt, Stream, StreamExt};
 use http::StatusCode;
 use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
-use kube::api::PatchParams;
+use kube::api::{PatchParams, PostParams};
+use kube::ResourceExt;
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::convert::TryFrom;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::Sender;
+use tokio::sync::{watch, Notify};
 use tokio_retry::strategy::ExponentialBackoff;
 
 type Api = kube::Api<LeaseObject>;
 
+/// Shortest lease duration accepted, see [LeaseLock::with_lease_duration].
+const MIN_LEASE_DURATION: Duration = Duration::from_millis(100);
+
+/// Fraction of the lease duration left when a renewal is due,
+/// see [LeaseLockClient::renew_interval].
+const RENEWAL_SLACK: (u32, u32) = (3, 5);
+
+/// Round trips measured by [LeaseLock::check_round_trips].
+const RTT_SAMPLES: u32 = 3;
+
+/// Multiple of the slowest round trip that must fit into the time left when a renewal is due.
+const SAFE_RTT_MULTIPLE: u32 = 4;
+
+/// Largest payload accepted by [LeaseGuard::release_with_handover].
+const MAX_HANDOVER_PAYLOAD: usize = 64 * 1024;
+
+/// Default for [LeaseLock::with_stale_retries].
+const DEFAULT_STALE_RETRIES: u32 = 2;
+
+/// Window over which replicas that find the lease missing spread their attempts to create it.
+const CREATE_SPREAD: Duration = Duration::from_secs(1);
+
+/// Single-threaded runtime driving renewals of locks configured with
+/// [LeaseLock::with_dedicated_renewal_runtime].
+static RENEWAL_RUNTIME: Lazy<Handle> = Lazy::new(|| {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("build renewal runtime");
+    let handle = runtime.handle().clone();
+    std::thread::Builder::new()
+        .name("lease-renewal".into())
+        .spawn(move || runtime.block_on(futures::future::pending::<()>()))
+        .expect("spawn renewal thread");
+    handle
+});
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("timeout waiting for acquire")]
-    AcquireTimeout,
+    /// Acquisition gave up with `remaining` time left before the deadline: it gives up early
+    /// when the next attempt could not complete in time.
+    /// `history` holds the last observations made while waiting, see
+    /// [LeaseLock::with_timeout_history].
+    #[error("timeout waiting for acquire ({remaining:?} left)")]
+    AcquireTimeout {
+        remaining: Duration,
+        history: Vec<Breadcrumb>,
+    },
+
+    #[error("timeout waiting for warm-up after acquire")]
+    WarmUpTimeout,
+
+    #[error("invalid configuration: {0}")]
+    Config(String),
+
+    #[error("timeout waiting for a ready successor")]
+    HandoverTimeout,
+
+    #[error("failed to acquire fenced resource: {0}")]
+    FencedResource(BoxError),
 
     #[error("Integer overflow in duration value")]
     IntOverflow(#[from] std::num::TryFromIntError),
 
+    #[error("lease lost while held")]
+    Lost,
+
+    /// The lock was drained for shutdown, see [LeaseLock::drain].
+    #[error("lease lock drained")]
+    Drained,
+
+    /// The time left when a renewal is due, `margin`, isn't a safe multiple of the round trip
+    /// to the API server, see [LeaseLock::check_round_trips].
+    #[error("renewal margin {margin:?} unsafe for API server round trip {rtt:?}")]
+    UnsafeLeaseDuration { margin: Duration, rtt: Duration },
+
+    #[error("fenced off: object was written under fencing token {seen}, ours is {token}")]
+    Fenced { token: i32, seen: i32 },
+
     #[error("key {0} not found in Lease")]
     Format(String),
 
@@ -27,29 +136,544 @@ pub enum Error {
     Kube(#[from] kube::Error),
 }
 
+impl Error {
+    /// HTTP status code of an error response of the API server.
+    pub fn status_code(&self) -> Option<StatusCode> {
+        match self {
+            Error::Kube(kube::Error::Api(api_err)) => StatusCode::from_u16(api_err.code).ok(),
+            _ => None,
+        }
+    }
+
+    /// Machine-readable reason of an error response of the API server, e.g. `Conflict`
+    /// or `TooManyRequests`.
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            Error::Kube(kube::Error::Api(api_err)) if !api_err.reason.is_empty() => {
+                Some(&api_err.reason)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the API server could not be reached or is unavailable, see [OutageMode].
+    pub fn is_unreachable(&self) -> bool {
+        match self {
+            Error::Kube(kube::Error::HyperError(_) | kube::Error::Service(_)) => true,
+            Error::Kube(kube::Error::Api(api_err)) => matches!(api_err.code, 502..=504),
+            _ => false,
+        }
+    }
+
+    pub(crate) fn acquire_timeout(deadline: Instant) -> Self {
+        Error::AcquireTimeout {
+            remaining: deadline.saturating_duration_since(Instant::now()),
+            history: Vec::new(),
+        }
+    }
+}
+
+/// Observation of the lease made while waiting for it, see [LeaseLock::with_timeout_history].
+#[derive(Clone, Debug)]
+pub struct Breadcrumb {
+    pub holder: Option<String>,
+    pub expires_at: UtcInstant,
+    /// Wait before looking again.
+    pub backoff: Duration,
+}
+
+/// The last `capacity` breadcrumbs of an acquisition.
+struct Breadcrumbs {
+    capacity: usize,
+    crumbs: VecDeque<Breadcrumb>,
+}
+
+impl Breadcrumbs {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            crumbs: VecDeque::new(),
+        }
+    }
+
+    fn push(&mut self, crumb: impl FnOnce() -> Breadcrumb) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.crumbs.len() == self.capacity {
+            self.crumbs.pop_front();
+        }
+        self.crumbs.push_back(crumb());
+    }
+}
+
+/// Verdict of a pre-acquire hook, see [LeaseLock::with_pre_acquire_hook].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    /// Take over the lease.
+    Proceed,
+    /// Don't take over the lease now; back off and ask again later.
+    Veto,
+}
+
+/// Outcome of [LeaseLock::try_acquire_state].
+// Short-lived and matched on right away, so the guard isn't boxed.
+#[allow(clippy::large_enum_variant)]
+pub enum TryAcquire {
+    Acquired(LeaseGuard),
+    /// The lease couldn't be acquired right away; its state as last read.
+    Held(LeaseState),
+}
+
+/// Clock that timestamps written to the lease are taken from, see [LeaseLock::with_time_source].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeSource {
+    /// Local wall clock.
+    Local,
+    /// API server clock, estimated from the server's timestamps of earlier writes.
+    Server,
+}
+
+/// What a held lease does while the API server is unreachable, see [LeaseLock::with_outage_mode].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutageMode {
+    /// Keep retrying renewals and keep the guard held, leaving the decision to the retry policy.
+    Retry,
+    /// Keep acting as holder until the lease expires as of the last successful renewal,
+    /// then give up ownership.
+    UntilExpiry,
+    /// Give up ownership on the first failure to reach the API server.
+    StopImmediately,
+}
+
+type ErrorObserver = Arc<dyn Fn(&Error, Operation) + Send + Sync>;
+
+type LeaseTemplate = Arc<dyn Fn(&mut LeaseObject) + Send + Sync>;
+
+type WarmUpHook = Arc<dyn Fn(&LeaseState) -> BoxFuture<'static, ()> + Send + Sync>;
+
+/// Guard shared by the tasks of one holder, see [LeaseLock::acquire_shared].
+type SharedGuard = Arc<tokio::sync::Mutex<Weak<LeaseGuard>>>;
+
+type PreAcquireHook = Arc<dyn Fn(&LeaseState) -> BoxFuture<'static, Decision> + Send + Sync>;
+
+/// Release of a dropped guard delayed by [LeaseLock::with_release_debounce], taken back by a
+/// re-acquire of the same holder.
+struct DebouncedRelease {
+    correlation_id: String,
+    lease_state: LeaseState,
+    fence: Arc<Fence>,
+    /// Time of the last successful renewal by the dropped guard.
+    renewed: Instant,
+    abort_handle: AbortHandle,
+}
+
 #[derive(Clone)]
-struct LeaseLockClient {
-    lease_name: String,
+struct WarmUp {
+    timeout: Duration,
+    hook: WarmUpHook,
+}
+
+/// Api the lease is reached through, with the state tied to it; swapped by [LeaseLock::rebind].
+#[derive(Clone)]
+struct Binding {
     api: Api,
-    lease_duration_sec: i32,
+    created: Arc<tokio::sync::OnceCell<()>>,
+    cache: Arc<LeaseCache>,
+}
+
+#[derive(Clone)]
+pub(crate) struct LeaseLockClient {
+    lease_name: String,
+    /// Shared by clones, so that a rebind reaches guards, observers and other tasks.
+    binding: Arc<Mutex<Binding>>,
+    lease_duration: Duration,
     expo: ExponentialBackoff,
+    pre_acquire_hook: Option<PreAcquireHook>,
+    warm_up: Option<WarmUp>,
+    holder_salt: Option<String>,
+    condition_reporter: Option<ConditionReporter>,
+    field_manager: String,
+    stall_warning_percent: Option<u32>,
+    dedicated_renewal_runtime: bool,
+    contention: Arc<Contention>,
+    heartbeat_interval: Option<Duration>,
+    renew_interval: Option<Duration>,
+    verify_write: bool,
+    strict_takeover: bool,
+    fenced_resources: Vec<Arc<dyn FencedResource>>,
+    schema_version: u32,
+    jitter: Option<Arc<dyn RandomSource>>,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
+    state_file: Option<StateFile>,
+    error_observer: Option<ErrorObserver>,
+    audit_annotations: Annotations,
+    time_source: TimeSource,
+    status_annotations: bool,
+    server_offset: Arc<Mutex<Option<chrono::Duration>>>,
+    health_informed_polling: bool,
+    renewal_stats: Arc<RenewalStats>,
+    spec_only_apply: bool,
+    bulk_watch: Option<BulkWatch>,
+    zone: Option<String>,
+    zone_spread_delay: Option<Duration>,
+    lease_template: Option<LeaseTemplate>,
+    incumbent_grace: Option<Duration>,
+    stale_retries: u32,
+    timeout_history: usize,
+    outage_mode: OutageMode,
+    release_debounce: Option<Duration>,
+    /// Pending releases delayed by `release_debounce`, by holder identity.
+    debounced: Arc<Mutex<HashMap<String, DebouncedRelease>>>,
+    renewal_client: Option<kube::Client>,
+    auto_create: bool,
+    /// Set by [LeaseLock::drain] to refuse new acquisitions, shared by clones.
+    drained: Arc<AtomicBool>,
 }
 
 /// Represents RAII lock based on k8s lease resource.
+/// Clones share inflight operations, shared guards and the [kube::Api] the lock is bound to, so a
+/// lock can be handed to many tasks.
+#[derive(Clone)]
 pub struct LeaseLock {
     client: LeaseLockClient,
-    completion_tx: Sender<()>,
-    completion_rx: Receiver<()>,
+    completion: Completion,
+    shared_guards: Arc<Mutex<BTreeMap<String, SharedGuard>>>,
 }
 
 /// RAII implementation of a 'scoped lock' of k8s lease.
 /// When dropped, schedules unlock task.
 /// To wait until unlocking is completed, see [LeaseLock::complete_all_operations].
 pub struct LeaseGuard {
-    api: Api,
+    client: LeaseLockClient,
     lease_state: LeaseState,
     abort_handle: AbortHandle,
     completion_tx: Sender<()>,
+    fence: Arc<Fence>,
+    renewal: Arc<RenewalControl>,
+    ownership: Arc<Ownership>,
+    /// Renewal stopped and ownership given up, see [LeaseGuard::stop].
+    stopped: bool,
+    /// Released by [LeaseGuard::release], so drop has nothing left to release.
+    released: bool,
+}
+
+/// Ownership of a held lease as known locally, shared between a [LeaseGuard] and its renewal task.
+struct Ownership {
+    /// Leader-scoped tasks, or None once ownership is lost.
+    tasks: Mutex<Option<Vec<AbortHandle>>>,
+    lost_tx: watch::Sender<bool>,
+    lost_rx: watch::Receiver<bool>,
+    /// Whether ownership is still held, for lock-free checks, see [LeaseGuard::is_held].
+    held: AtomicBool,
+    /// Time of the last successful write of the lease, None once ownership is lost.
+    renewed_tx: watch::Sender<Option<Instant>>,
+    registration: Registration,
+    /// Random id of this acquisition, see [LeaseGuard::correlation_id].
+    correlation_id: String,
+}
+
+impl Ownership {
+    fn new(registration: Registration, correlation_id: String, renewed: Instant) -> Self {
+        let (lost_tx, lost_rx) = watch::channel(false);
+        let (renewed_tx, _) = watch::channel(Some(renewed));
+        Self {
+            tasks: Mutex::new(Some(Vec::new())),
+            held: AtomicBool::new(true),
+            lost_tx,
+            lost_rx,
+            renewed_tx,
+            registration,
+            correlation_id,
+        }
+    }
+
+    /// Mark ownership lost, abort leader-scoped tasks and wake [LeaseGuard::lost] waiters,
+    /// all under one lock. Return false if it was lost already.
+    fn lose(&self) -> bool {
+        let mut tasks = self.tasks.lock().unwrap();
+        match tasks.take() {
+            None => false,
+            Some(scoped) => {
+                self.held.store(false, Ordering::SeqCst);
+                scoped.iter().for_each(AbortHandle::abort);
+                self.registration.remove();
+                self.renewed_tx.send_replace(None);
+                let _ = self.lost_tx.send(true);
+                true
+            }
+        }
+    }
+}
+
+/// Pause switch shared between a [LeaseGuard] and its renewal task.
+#[derive(Default)]
+struct RenewalControl {
+    paused: AtomicBool,
+    resumed: Notify,
+    safety_timer: Mutex<Option<AbortHandle>>,
+}
+
+impl RenewalControl {
+    fn resume(&self) {
+        if let Some(timer) = self.safety_timer.lock().unwrap().take() {
+            timer.abort();
+        }
+        if self.paused.swap(false, Ordering::SeqCst) {
+            self.resumed.notify_one();
+        }
+    }
+}
+
+impl LeaseGuard {
+    /// State of the lease as of acquisition.
+    pub fn lease_state(&self) -> &LeaseState {
+        &self.lease_state
+    }
+
+    pub(crate) fn client(&self) -> &LeaseLockClient {
+        &self.client
+    }
+
+    /// Release the lease right away rather than in the background as on drop, and return its
+    /// final state, so that errors and completion can be observed by the caller. Should the
+    /// returned future be dropped before the release completes, drop releases the lease instead.
+    pub async fn release(mut self) -> Result<LeaseState, Error> {
+        log::debug!(
+            "{}.release({:?})",
+            &self.lease_state.lease_name,
+            &self.lease_state.holder
+        );
+        self.stop();
+        let released = self
+            .client
+            .release_held(
+                &self.lease_state,
+                &self.fence,
+                None,
+                &self.ownership.correlation_id,
+            )
+            .await;
+        self.released = true;
+        released
+    }
+
+    /// Release the lease like [LeaseGuard::release], leaving `payload` (e.g. serialized state
+    /// or offsets) for the next holder to pick up from [LeaseGuard::handover_payload], for warm
+    /// handoffs without an external store. The payload is kept in an annotation of the lease,
+    /// so it must be small: up to 64KiB.
+    pub async fn release_with_handover(
+        mut self,
+        payload: impl Into<String>,
+    ) -> Result<LeaseState, Error> {
+        let payload = payload.into();
+        if payload.len() > MAX_HANDOVER_PAYLOAD {
+            return Err(Error::Config(format!(
+                "handover payload of {} bytes over {}",
+                payload.len(),
+                MAX_HANDOVER_PAYLOAD
+            )));
+        }
+        log::debug!(
+            "{}.release_with_handover({:?}, {} bytes)",
+            &self.lease_state.lease_name,
+            &self.lease_state.holder,
+            payload.len()
+        );
+        self.stop();
+        let released = self
+            .client
+            .release_held(
+                &self.lease_state,
+                &self.fence,
+                Some(&payload),
+                &self.ownership.correlation_id,
+            )
+            .await;
+        self.released = true;
+        released
+    }
+
+    /// Payload the previous holder left on release, see [LeaseGuard::release_with_handover].
+    pub fn handover_payload(&self) -> Option<&str> {
+        self.lease_state.handover.as_deref()
+    }
+
+    /// Stop renewal and give up local ownership, once.
+    fn stop(&mut self) {
+        if std::mem::replace(&mut self.stopped, true) {
+            return;
+        }
+        self.abort_handle.abort();
+        self.ownership.lose();
+        metrics::record(&self.lease_state.lease_name, |m| {
+            m.held -= 1;
+            m.correlation_ids.remove(&self.ownership.correlation_id);
+        });
+        self.renewal.resume();
+        #[cfg(feature = "debug-lock-order")]
+        lock_order::released(
+            self.lease_state.holder.as_deref().unwrap_or_default(),
+            &self.lease_state.lease_name,
+        );
+    }
+
+    /// Whether the lease is still held as far as this process knows: false once renewal found
+    /// it taken over, gave up renewing, or the guard is being dropped.
+    pub fn is_held(&self) -> bool {
+        self.ownership.held.load(Ordering::SeqCst)
+    }
+
+    /// Fencing token of this acquisition: the lease's `leaseTransitions`, which every acquisition
+    /// increments. Pass it along to downstream systems so they can reject writes of stale leaders
+    /// with an older token.
+    pub fn fencing_token(&self) -> i32 {
+        self.lease_state.transitions
+    }
+
+    /// Random id of this acquisition, attached to the logs, spans, span events and metrics of the
+    /// guard from acquire to release, to follow one leadership stint across systems.
+    pub fn correlation_id(&self) -> &str {
+        &self.ownership.correlation_id
+    }
+
+    /// Whether the lease is held and, as of the last successful renewal, hasn't expired yet.
+    /// Unlike [LeaseGuard::is_held], turns false once renewals kept failing for a whole lease
+    /// duration, even before renewal gives up: others may have taken the lease over meanwhile.
+    pub fn is_valid(&self) -> bool {
+        let renewed = *self.ownership.renewed_tx.borrow();
+        match renewed {
+            Some(renewed) => self.is_held() && renewed.elapsed() < self.client.lease_duration,
+            None => false,
+        }
+    }
+
+    /// Resolve once the lease is lost, see [LeaseGuard::is_held].
+    pub async fn lost(&self) {
+        let mut lost_rx = self.ownership.lost_rx.clone();
+        while !*lost_rx.borrow_and_update() {
+            if lost_rx.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Time of the last successful renewal (initially, of the acquisition), or None once the
+    /// lease is lost. Meant for watchdogs and liveness probes that must kill the process when
+    /// renewals stall.
+    pub fn renewals(&self) -> watch::Receiver<Option<Instant>> {
+        self.ownership.renewed_tx.subscribe()
+    }
+
+    /// Run `task` in the background while the lease is held: it is aborted as soon as ownership
+    /// is lost or the guard is dropped. If ownership is lost already, `task` isn't started.
+    pub fn spawn_scoped<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let (abort_handle, abort_reg) = AbortHandle::new_pair();
+        match self.ownership.tasks.lock().unwrap().as_mut() {
+            None => return,
+            Some(tasks) => tasks.push(abort_handle),
+        }
+        spawn_named(
+            &format!("lease-scoped:{}", &self.lease_state.lease_name),
+            None,
+            Abortable::new(task, abort_reg),
+        );
+    }
+
+    /// Stop renewing the lease, so that it expires and can be taken over should this process
+    /// hang during a risky operation. Unless resumed with [LeaseGuard::resume_renewal], renewal
+    /// resumes by itself before the lease expires. That safety timer runs on the caller's
+    /// runtime, so the lease is only let go if this runtime hangs.
+    pub fn pause_renewal(&self) {
+        log::debug!(
+            "{}.pause_renewal({:?})",
+            &self.lease_state.lease_name,
+            &self.lease_state.holder
+        );
+        let (timer, timer_reg) = AbortHandle::new_pair();
+        if let Some(previous) = self.renewal.safety_timer.lock().unwrap().replace(timer) {
+            previous.abort();
+        }
+        self.renewal.paused.store(true, Ordering::SeqCst);
+        let renewal = self.renewal.clone();
+        let delay = self.client.renew_interval();
+        spawn_named(
+            &format!("lease-pause:{}", &self.lease_state.lease_name),
+            None,
+            Abortable::new(
+                async move {
+                    tokio::time::sleep(delay).await;
+                    renewal.resume();
+                },
+                timer_reg,
+            ),
+        );
+    }
+
+    /// Resume renewal paused by [LeaseGuard::pause_renewal], renewing the lease right away.
+    pub fn resume_renewal(&self) {
+        log::debug!(
+            "{}.resume_renewal({:?})",
+            &self.lease_state.lease_name,
+            &self.lease_state.holder
+        );
+        self.renewal.resume();
+    }
+}
+
+impl LeaseGuard {
+    /// Release in the background after `debounce`, unless the same holder acquires the lease
+    /// again meanwhile, see [LeaseLockClient::resume_debounced].
+    fn release_debounced(&self, debounce: Duration, renewed: Instant) {
+        let identity = self.lease_state.holder.clone().unwrap_or_default();
+        let correlation_id = self.ownership.correlation_id.clone();
+        let (abort_handle, abort_reg) = AbortHandle::new_pair();
+        self.client.debounced.lock().unwrap().insert(
+            identity.clone(),
+            DebouncedRelease {
+                correlation_id: correlation_id.clone(),
+                lease_state: self.lease_state.clone(),
+                fence: self.fence.clone(),
+                renewed,
+                abort_handle,
+            },
+        );
+        let task_name = format!("lease-release:{}", &self.lease_state.lease_name);
+        spawn_named(&task_name, None, {
+            let client = self.client.clone();
+            let completion_tx = self.completion_tx.clone();
+            Abortable::new(
+                async move {
+                    tokio::time::sleep(debounce).await;
+                    let debounced = {
+                        let mut debounced = client.debounced.lock().unwrap();
+                        let pending = debounced.get(&identity);
+                        if matches!(pending, Some(p) if p.correlation_id == correlation_id) {
+                            debounced.remove(&identity)
+                        } else {
+                            // taken back by a re-acquire
+                            None
+                        }
+                    };
+                    if let Some(debounced) = debounced {
+                        let _ = client
+                            .release_held(
+                                &debounced.lease_state,
+                                &debounced.fence,
+                                None,
+                                &correlation_id,
+                            )
+                            .await;
+                    }
+                    drop(completion_tx);
+                },
+                abort_reg,
+            )
+        });
+    }
 }
 
 impl Drop for LeaseGuard {
@@ -59,477 +683,4383 @@ impl Drop for LeaseGuard {
             &self.lease_state.lease_name,
             &self.lease_state.holder
         );
-        self.abort_handle.abort();
-        tokio::spawn({
-            let api = self.api.clone();
+        let renewed = *self.ownership.renewed_tx.borrow();
+        self.stop();
+        if self.released {
+            return;
+        }
+        if let (Some(debounce), Some(renewed)) = (self.client.release_debounce, renewed) {
+            // unless the lease would be too close to expiry to be taken back by then
+            if renewed.elapsed() + debounce < self.client.debounce_margin() {
+                self.release_debounced(debounce, renewed);
+                return;
+            }
+        }
+        let task_name = format!("lease-release:{}", &self.lease_state.lease_name);
+        spawn_named(&task_name, None, {
+            let client = self.client.clone();
             let lease_state = self.lease_state.clone();
             let completion_tx = self.completion_tx.clone();
+            let fence = self.fence.clone();
+            let correlation_id = self.ownership.correlation_id.clone();
             async move {
-                match release_lock(api, &lease_state).await {
-                    Err(e) => log::error!(
-                        "{}.release_lock({:?}) => {}",
-                        &lease_state.lease_name,
-                        &lease_state.holder,
-                        e
-                    ),
-                    Ok(_) => log::debug!(
-                        "release_lock({}, {:?}) => OK",
-                        &lease_state.lease_name,
-                        &lease_state.holder
-                    ),
-                }
+                let _ = client
+                    .release_held(&lease_state, &fence, None, &correlation_id)
+                    .await;
                 drop(completion_tx);
             }
         });
     }
 }
 
-async fn release_lock(api: Api, lease_state: &LeaseState) -> Result<LeaseState, Error> {
-    let patch: LeaseObject = serde_json::from_value(serde_json::json!({
-        "apiVersion": "coordination.k8s.io/v1",
-        "kind": "Lease",
-        "metadata": {
-            "name": &lease_state.lease_name,
-            "resourceVersion": &lease_state.resource_version,
-        },
-        "spec": {
-            "holderIdentity": serde_json::json!(null),
-        }
-    }))?;
-
-    api.patch(
-        &lease_state.lease_name,
-        &PatchParams::apply("lease-rs").force(),
-        &kube::api::Patch::Apply(&patch),
-    )
-    .await
-    .map(LeaseState::try_from)?
-}
-
 impl LeaseLock {
     pub fn new(api: Api, lease_name: String) -> Self {
-        let (completion_tx, completion_rx) = channel(1);
+        let defaults = defaults::current();
         Self {
             client: LeaseLockClient {
-                api,
+                binding: Arc::new(Mutex::new(Binding {
+                    api,
+                    created: Default::default(),
+                    cache: Default::default(),
+                })),
                 lease_name,
-                lease_duration_sec: 10,
-                expo: ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1)),
+                lease_duration: defaults.lease_duration,
+                expo: defaults.expo,
+                pre_acquire_hook: None,
+                warm_up: None,
+                holder_salt: None,
+                condition_reporter: None,
+                field_manager: defaults.field_manager,
+                stall_warning_percent: None,
+                dedicated_renewal_runtime: false,
+                contention: Default::default(),
+                heartbeat_interval: None,
+                renew_interval: None,
+                verify_write: false,
+                strict_takeover: false,
+                fenced_resources: Vec::new(),
+                schema_version: SCHEMA_VERSION,
+                jitter: None,
+                retry_policy: None,
+                state_file: None,
+                error_observer: None,
+                audit_annotations: Annotations::new(),
+                time_source: TimeSource::Local,
+                status_annotations: false,
+                server_offset: Default::default(),
+                health_informed_polling: false,
+                renewal_stats: Default::default(),
+                spec_only_apply: false,
+                bulk_watch: None,
+                zone: None,
+                zone_spread_delay: None,
+                lease_template: None,
+                incumbent_grace: None,
+                stale_retries: DEFAULT_STALE_RETRIES,
+                timeout_history: 0,
+                outage_mode: OutageMode::Retry,
+                release_debounce: None,
+                debounced: Default::default(),
+                renewal_client: None,
+                auto_create: false,
+                drained: Default::default(),
             },
-            completion_tx: completion_tx,
-            completion_rx: completion_rx,
+            completion: Completion::new(),
+            shared_guards: Default::default(),
         }
     }
 
-    /// Configure lease expiry time. Default is 10 seconds.
+    /// Configure lease expiry time. Default is 10 seconds, unless changed by [crate::defaults].
     /// Only matters if normal unlocking (via [LeaseGuard]) did not happend for some reason.
     pub fn with_lease_duration_sec(mut self, sec: i32) -> Self {
-        self.client.lease_duration_sec = sec;
+        self.client.lease_duration = Duration::from_secs(sec.max(0) as u64);
+        self
+    }
+
+    /// Configure lease expiry time with millisecond precision, for short leases.
+    /// The lease object only stores whole seconds, so other contenders see the duration rounded
+    /// up, while this process renews and tracks validity on the precise value.
+    /// Must be at least 100ms, otherwise acquire fails with [Error::Config].
+    pub fn with_lease_duration(mut self, duration: Duration) -> Self {
+        self.client.lease_duration = duration;
         self
     }
 
     /// Customize backoff policy. Default is
-    /// `ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1))`
+    /// `ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1))`,
+    /// unless changed by [crate::defaults].
     pub fn with_expo_backoff(mut self, expo: ExponentialBackoff) -> Self {
         self.client.expo = expo;
         self
     }
 
-    /// Wait for all inflight operations on this lock to complete.
-    /// Can be used for graceful shutdown to make sure all scheduled unlocks complete.
-    pub async fn complete_all_operations(&mut self) {
-        let (completion_tx, completion_rx) = channel(1);
-        self.completion_tx = completion_tx;
-        let _ = self.completion_rx.recv().await;
-        self.completion_rx = completion_rx;
+    /// Install a hook evaluated after the lease is observed free and before it is taken over.
+    /// Returning [Decision::Veto] makes acquire back off and retry (e.g. until the local replica
+    /// is warm), still bounded by `acquire_timeout`.
+    pub fn with_pre_acquire_hook<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: Fn(&LeaseState) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Decision> + Send + 'static,
+    {
+        self.client.pre_acquire_hook = Some(Arc::new(move |lease_state: &LeaseState| {
+            hook(lease_state).boxed()
+        }));
+        self
     }
 
-    /// Acquire the lock; return [LeaseGuard] RAII object. Lease renewal will be done in background
-    /// as long as [LeaseGuard] exists.
-    ///
-    /// # Arguments
-    ///
-    /// `holder_id` - represents holder of the lock.
-    /// `acquire_timeout` - return [Error::AcquireTimeout] error if acquire did not complete within the
-    /// timeout.
-    pub async fn acquire(
-        &self,
-        holder_id: &str,
-        acquire_timeout: Option<Duration>,
-    ) -> Result<LeaseGuard, Error> {
-        self.client
-            .acquire(holder_id, acquire_timeout, self.completion_tx.clone())
-            .await
+    /// Install a readiness barrier awaited after the lease is written and before acquire returns.
+    /// The lease is renewed meanwhile, so slow warm-up doesn't lose it. If the barrier doesn't
+    /// complete within `timeout`, the lease is released and [Error::WarmUpTimeout] is returned.
+    pub fn with_warm_up<F, Fut>(mut self, timeout: Duration, hook: F) -> Self
+    where
+        F: Fn(&LeaseState) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.client.warm_up = Some(WarmUp {
+            timeout,
+            hook: Arc::new(move |lease_state: &LeaseState| hook(lease_state).boxed()),
+        });
+        self
     }
 
-    /// Acquire the lock if it can be done immediately. If not, return None.
-    pub async fn try_acquire(&self, holder_id: &str) -> Result<Option<LeaseGuard>, Error> {
-        match self.acquire(holder_id, Some(Duration::ZERO)).await {
-            Ok(lg) => Ok(Some(lg)),
-            Err(e) => match e {
-                Error::AcquireTimeout => Ok(None),
-                _ => Err(e),
-            },
-        }
+    /// Configure field manager used for server-side apply patches. Default is `lease-rs`,
+    /// unless changed by [crate::defaults].
+    pub fn with_field_manager(mut self, field_manager: impl Into<String>) -> Self {
+        self.client.field_manager = field_manager.into();
+        self
     }
-}
 
-impl LeaseLockClient {
-    pub async fn acquire(
-        &self,
-        holder_id: &str,
-        acquire_timeout: Option<Duration>,
-        completion_tx: Sender<()>,
-    ) -> Result<LeaseGuard, Error> {
-        log::debug!(
-            "{}.acquire({}, {:?})",
-            &self.lease_name,
-            holder_id,
-            acquire_timeout
-        );
+    /// Warn when a renewal tick fires later than scheduled by more than `percent` of the lease
+    /// duration. Such delays mean the executor is starved and the lease is at risk of expiring.
+    pub fn with_stall_warning(mut self, percent: u32) -> Self {
+        self.client.stall_warning_percent = Some(percent);
+        self
+    }
 
-        let deadline = acquire_timeout.map(|to| Instant::now() + to);
+    /// Run renewals on a dedicated single-threaded runtime shared by all such locks, so a
+    /// blocked or busy application runtime can't starve them. Note that requests still go
+    /// through the [kube::Client] of the lock, so it should not be tied to the busy runtime either.
+    pub fn with_dedicated_renewal_runtime(mut self, dedicated: bool) -> Self {
+        self.client.dedicated_renewal_runtime = dedicated;
+        self
+    }
 
-        loop {
-            let lease_state = self.wait_free(deadline, &holder_id).await?;
-            let lease_state = self.try_overwrite(holder_id, lease_state).await?;
-            if lease_state.owner() == Some(holder_id) {
-                return Ok(LeaseGuard {
-                    api: self.api.clone(),
-                    lease_state,
-                    abort_handle: self.clone().schedule_renewal(holder_id.to_string()),
-                    completion_tx,
-                });
-            }
-        }
+    /// Stamp the lease with a heartbeat annotation every `interval` while held, independently of
+    /// renewals. Meant for long leases (hours): the lease is renewed rarely, while
+    /// [LeaseLock::reap_orphaned] can still tell a dead holder from a live one.
+    pub fn with_heartbeat(mut self, interval: Duration) -> Self {
+        self.client.heartbeat_interval = Some(interval);
+        self
     }
 
-    #[must_use]
-    fn schedule_renewal(self, holder_id: String) -> AbortHandle {
-        let (abort_handle, abort_reg) = AbortHandle::new_pair();
-        tokio::spawn(Abortable::new(
-            async move {
-                loop {
-                    tokio::time::sleep(Duration::from_millis(
-                        (self.lease_duration_sec * 400) as u64,
-                    ))
-                    .await;
-                    match self.get_state().await {
-                        Ok(lease_state) => {
-                            if lease_state.owner().as_ref() == Some(&holder_id.as_str()) {
-                                if let Err(e) = self.renew_lease(lease_state).await {
-                                    log::error!(
-                                        "renew_lease({}, {}) => {}",
-                                        self.lease_name,
-                                        holder_id,
-                                        e
-                                    );
-                                }
-                            } else {
-                                log::warn!(
-                                    "lost ownership; new owner: {:?}; stop renewal",
-                                    lease_state.owner()
-                                );
-                                return;
-                            }
-                        }
-                        Err(e) => log::error!(
-                            "schedule_renewal({}, {}) => {}",
-                            self.lease_name,
-                            holder_id,
-                            e
-                        ),
-                    }
-                }
-            },
-            abort_reg,
-        ));
+    /// Renew the held lease every `interval`. Default is 2/5 of the lease duration.
+    /// Must be shorter than the lease duration, otherwise acquire fails with [Error::Config].
+    pub fn with_renew_interval(mut self, interval: Duration) -> Self {
+        self.client.renew_interval = Some(interval);
+        self
+    }
 
-        abort_handle
+    /// Delay the release of a dropped guard by `debounce`, and cancel it if the same holder
+    /// acquires the lease again meanwhile, taking the held lease back without writing it.
+    /// Saves a release and acquire write pair for scoped locks dropped and re-acquired in quick
+    /// succession, at the cost of others waiting up to `debounce` longer. [LeaseGuard::release]
+    /// is not delayed. Must be shorter than the lease duration minus the renew interval,
+    /// otherwise acquire fails with [Error::Config].
+    pub fn with_release_debounce(mut self, debounce: Duration) -> Self {
+        self.client.release_debounce = Some(debounce);
+        self
     }
 
-    async fn renew_lease(&self, lease_state: LeaseState) -> Result<LeaseState, Error> {
-        let now: &str = &chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, false);
-        let patch: LeaseObject = serde_json::from_value(serde_json::json!({
-            "apiVersion": "coordination.k8s.io/v1",
-            "kind": "Lease",
-            "metadata": {
-                "name": &lease_state.lease_name,
-                "resourceVersion": &lease_state.resource_version,
+    /// After taking over the lease, read it back and confirm it still holds our identity and
+    /// acquire time before returning the guard. If another writer overtook us, back off and
+    /// contend again. Costs an extra GET per acquire.
+    pub fn with_write_verification(mut self, verify: bool) -> Self {
+        self.client.verify_write = verify;
+        self
+    }
+
+    /// Refuse to write the lease unless a read right before the write finds it free, and verify
+    /// the write afterwards as with [LeaseLock::with_write_verification]. For users who prefer
+    /// safety over acquisition latency; costs two extra GETs per takeover.
+    pub fn with_strict_takeover(mut self, strict: bool) -> Self {
+        self.client.strict_takeover = strict;
+        self
+    }
+
+    /// While waiting for a held lease, poll slowly as long as the holder keeps renewing it: sleep
+    /// until its current term would expire instead of backing off towards `max_delay`. Once
+    /// renewals stop arriving, fall back to the regular backoff. Cuts API load of large fleets of
+    /// waiters, at the cost of noticing a voluntary release up to a lease duration late.
+    pub fn with_health_informed_polling(mut self, enabled: bool) -> Self {
+        self.client.health_informed_polling = enabled;
+        self
+    }
+
+    /// Bind a resource in another system to the lease: it is acquired right after the lease, and
+    /// released before the lease is released or as soon as the lease is found lost. Resources
+    /// are acquired in registration order and released in reverse order.
+    pub fn with_fenced_resource(mut self, resource: Arc<dyn FencedResource>) -> Self {
+        self.client.fenced_resources.push(resource);
+        self
+    }
+
+    /// Write crate annotations in an older schema version, so that replicas still running an
+    /// older crate version understand them during a rollout. Default is [SCHEMA_VERSION].
+    pub fn with_schema_version(mut self, version: u32) -> Self {
+        self.client.schema_version = version.min(SCHEMA_VERSION);
+        self
+    }
+
+    /// Randomize each backoff delay to between half and all of its value, drawing from `random`,
+    /// so that contenders started together don't poll in lockstep.
+    /// Pass a [SeededRandom](crate::SeededRandom) for reproducible runs.
+    pub fn with_backoff_jitter(mut self, random: Arc<dyn RandomSource>) -> Self {
+        self.client.jitter = Some(random);
+        self
+    }
+
+    /// Consult `policy` on every error of acquire, renewal and release, instead of the built-in
+    /// behavior (see [RetryPolicy]). Retries during acquire stay bounded by `acquire_timeout`.
+    pub fn with_retry_policy(mut self, policy: Arc<dyn RetryPolicy>) -> Self {
+        self.client.retry_policy = Some(policy);
+        self
+    }
+
+    /// Call `observer` on every error of acquire, renewal and release, including ones that are
+    /// retried or only logged, e.g. to count swallowed renewal errors in telemetry.
+    pub fn with_error_observer<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(&Error, Operation) + Send + Sync + 'static,
+    {
+        self.client.error_observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Write salted SHA-256 hashes of holder ids to the cluster instead of the ids themselves,
+    /// for environments where pod or host names are sensitive. Local logs keep the plain ids.
+    /// All contenders of the lease should use the same salt.
+    pub fn with_hashed_holder(mut self, salt: impl Into<String>) -> Self {
+        self.client.holder_salt = Some(salt.into());
+        self
+    }
+
+    /// Record the lease in the file at `path` whenever it is acquired or released, so that
+    /// [LeaseLock::resume] can pick up where a restarted process left off. A process that finds
+    /// its lease lost while it was down waits `cooldown` before contending again.
+    pub fn with_state_file(mut self, path: impl Into<PathBuf>, cooldown: Duration) -> Self {
+        self.client.state_file = Some(StateFile::new(path.into(), cooldown));
+        self
+    }
+
+    /// Write `annotations` (e.g. who, why, ticket id) to the lease on every acquire, kept until
+    /// release, for change-management audits of production locks.
+    pub fn with_audit_annotations(mut self, annotations: BTreeMap<String, String>) -> Self {
+        self.client.audit_annotations = annotations;
+        self
+    }
+
+    /// Take timestamps written to the lease (renewTime, acquireTime) from `source`. With
+    /// [TimeSource::Server], the offset of the local clock is estimated to within a second from
+    /// the server's timestamps of this manager's writes, reducing sensitivity to client clock
+    /// drift; until the first write, the local clock is used. Default is [TimeSource::Local].
+    pub fn with_time_source(mut self, source: TimeSource) -> Self {
+        self.client.time_source = source;
+        self
+    }
+
+    /// Clock currently used for timestamps, see [LeaseLock::with_time_source].
+    pub fn time_source(&self) -> TimeSource {
+        self.client.now().1
+    }
+
+    /// Maintain human-readable annotations with the holder, expiry time and renewal count of the
+    /// lease, so that `kubectl describe lease` shows its status at a glance. They are written
+    /// along with acquires and renewals, without extra writes.
+    pub fn with_status_annotations(mut self, enabled: bool) -> Self {
+        self.client.status_annotations = enabled;
+        self
+    }
+
+    /// Write only the spec of the lease: acquires and renewals carry no annotations, so that the
+    /// lock never takes over metadata managed by other tools (e.g. GitOps). Takeover records,
+    /// schema version and heartbeats are not kept; status and audit annotations can't be used.
+    pub fn with_spec_only_apply(mut self, spec_only: bool) -> Self {
+        self.client.spec_only_apply = spec_only;
+        self
+    }
+
+    /// Create the lease object on acquire if it doesn't exist, rather than failing with a 404.
+    /// Its labels and annotations can be set with [LeaseLock::with_lease_template].
+    pub fn with_create_if_missing(mut self, create: bool) -> Self {
+        self.client.auto_create = create;
+        self
+    }
+
+    /// Customize the lease object before the crate creates a missing lease, e.g. to add
+    /// finalizers, labels or annotations.
+    pub fn with_lease_template<F>(mut self, template: F) -> Self
+    where
+        F: Fn(&mut LeaseObject) + Send + Sync + 'static,
+    {
+        self.client.lease_template = Some(Arc::new(template));
+        self
+    }
+
+    /// How many times a takeover that conflicted only because the lease changed since it was
+    /// read (e.g. a holder released it meanwhile) is retried with a fresh read, before backing
+    /// off. Default is 2; 0 backs off right away.
+    pub fn with_stale_retries(mut self, retries: u32) -> Self {
+        self.client.stale_retries = retries;
+        self
+    }
+
+    /// Configure what a held lease does while the API server is unreachable. Default is
+    /// [OutageMode::Retry]: the guard stays held no matter how long renewals fail.
+    pub fn with_outage_mode(mut self, mode: OutageMode) -> Self {
+        self.client.outage_mode = mode;
+        self
+    }
+
+    /// Renew through `client` rather than the client of the lock's [kube::Api], e.g. one with
+    /// tighter timeouts and no proxy, so that a slow shared client can't delay renewals.
+    /// Acquire and release still go through the general client.
+    pub fn with_renewal_client(mut self, client: kube::Client) -> Self {
+        self.client.renewal_client = Some(client);
+        self
+    }
+
+    /// Keep the last `observations` holders, expiries and backoffs seen while waiting for the
+    /// lease, and report them in [Error::AcquireTimeout] to tell why the lock wasn't acquired.
+    /// Default is 0, keeping none.
+    pub fn with_timeout_history(mut self, observations: usize) -> Self {
+        self.client.timeout_history = observations;
+        self
+    }
+
+    /// When the lease expired only just now (within a lease duration), wait `grace` before taking
+    /// it over, so that a quickly restarting holder (e.g. in a CrashLoop blip) can reclaim it
+    /// without a failover. Voluntarily released leases are taken over right away.
+    pub fn with_incumbent_grace(mut self, grace: Duration) -> Self {
+        self.client.incumbent_grace = Some(grace);
+        self
+    }
+
+    /// Record the failure domain (e.g. availability zone) of holders of this lock in the lease.
+    /// It stays on the lease after release, so that successors know where the last leader ran.
+    pub fn with_zone(mut self, zone: impl Into<String>) -> Self {
+        self.client.zone = Some(zone.into());
+        self
+    }
+
+    /// Spread leadership across failure domains over time: when the lease is free and its last
+    /// holder ran in our zone (see [LeaseLock::with_zone]), wait `delay` before taking it, giving
+    /// candidates in other zones the first chance.
+    pub fn with_zone_spread(mut self, delay: Duration) -> Self {
+        self.client.zone_spread_delay = Some(delay);
+        self
+    }
+
+    /// Observe the lease through `bulk_watch`, shared with other locks, instead of a watch of its
+    /// own; waiting for the lease then polls the watched state instead of reading the lease.
+    /// The lease must match the label selector of `bulk_watch`.
+    pub fn with_bulk_watch(mut self, bulk_watch: &BulkWatch) -> Self {
+        self.client.bulk_watch = Some(bulk_watch.clone());
+        self
+    }
+
+    /// Mirror leadership into a status condition of a custom resource on every acquire and release.
+    /// Reporting is best-effort: failures are logged and don't affect the lock.
+    pub fn with_condition_reporter(mut self, reporter: ConditionReporter) -> Self {
+        self.client.condition_reporter = Some(reporter);
+        self
+    }
+
+    /// Wait for all inflight operations on this lock to complete.
+    /// Can be used for graceful shutdown to make sure all scheduled unlocks complete.
+    pub async fn complete_all_operations(&self) {
+        self.completion.complete_all().await;
+    }
+
+    /// Drain the lock for shutdown: refuse further acquisitions on it and its clones with
+    /// [Error::Drained], release `guards` one after another in the given order, e.g. dependents
+    /// before what they depend on, and wait for all inflight operations to complete. Return the
+    /// result of each release, in the order of `guards`.
+    ///
+    /// Other guards of this lock still held keep the wait going until they are dropped, just like
+    /// [LeaseLock::complete_all_operations]; they are logged from the [crate::held_leases]
+    /// registry.
+    pub async fn drain(
+        &self,
+        guards: impl IntoIterator<Item = LeaseGuard>,
+    ) -> Vec<Result<LeaseState, Error>> {
+        log::debug!("{}.drain()", &self.client.lease_name);
+        self.client.drained.store(true, Ordering::SeqCst);
+        let released = crate::release_all(guards, 1).await;
+        for held in registry::held_leases()
+            .iter()
+            .filter(|info| info.lease_name == self.client.lease_name)
+        {
+            log::warn!(
+                "{}.drain() => still held by {} ({}); waiting for its guard to drop",
+                &self.client.lease_name,
+                &held.holder,
+                &held.correlation_id
+            );
+        }
+        self.complete_all_operations().await;
+        released
+    }
+
+    /// Recent conflicts and in-process waiters of this lock, for shedding or deferring
+    /// lock-requiring work while the lease is hot.
+    pub fn contention_level(&self) -> ContentionLevel {
+        self.client.contention.level()
+    }
+
+    /// Observe the lease: an endless stream of its states as seen by a watch.
+    /// The watch is re-established internally after expiry or errors.
+    pub fn watch(&self) -> impl Stream<Item = LeaseState> + Send + 'static {
+        self.client.watch()
+    }
+
+    /// Endless stream of the time left until the lease of the current holder expires, or None
+    /// while it has no live holder, e.g. for a live countdown on a dashboard. Emitted every
+    /// `cadence` and on every change seen by the watch; the countdown is computed from the last
+    /// watched state, so the lease isn't polled.
+    pub fn ttl_stream(
+        &self,
+        cadence: Duration,
+    ) -> impl Stream<Item = Option<Duration>> + Send + 'static {
+        let states = self.client.watch().boxed();
+        stream::unfold(
+            (states, None::<LeaseState>),
+            move |(mut states, mut last)| async move {
+                if last.is_none() {
+                    last = Some(states.next().await?);
+                } else {
+                    tokio::select! {
+                        lease_state = states.next() => last = Some(lease_state?),
+                        _ = tokio::time::sleep(cadence) => {}
+                    }
+                }
+                let ttl = last
+                    .as_ref()
+                    .and_then(|lease_state| lease_state.owner().map(|_| lease_state.remaining()));
+                Some((ttl, (states, last)))
             },
-            "spec": {
-                "renewTime": now,
-                "holderIdentity": &lease_state.holder,
-            }
-        }))?;
+        )
+    }
 
-        self.api
-            .patch(
-                &lease_state.lease_name,
-                &PatchParams::apply("lease-rs").force(),
-                &kube::api::Patch::Apply(&patch),
+    pub(crate) fn client(&self) -> &LeaseLockClient {
+        &self.client
+    }
+
+    /// State of the lease, e.g. to tell who the leader is, no older than `max_staleness`.
+    /// Served from a watch started on first call; GETs the lease only when the watch hasn't
+    /// seen it within `max_staleness`, so request paths can ask cheaply.
+    pub async fn current_leader(&self, max_staleness: Duration) -> Result<LeaseState, Error> {
+        self.client.current_leader(max_staleness).await
+    }
+
+    /// Shortest lease duration safe by the renewal latencies and error rate observed by guards
+    /// of this lock, or None until enough renewals were observed. A warning is logged once if
+    /// the configured duration is shorter.
+    pub fn suggested_lease_duration(&self) -> Option<Duration> {
+        self.client
+            .renewal_stats
+            .suggest(self.client.renewal_slack())
+    }
+
+    /// Name of the lease.
+    pub fn name(&self) -> &str {
+        &self.client.lease_name
+    }
+
+    /// Namespace of the lease, as configured in the [kube::Api] the lock is bound to.
+    pub fn namespace(&self) -> Option<String> {
+        self.client.namespace()
+    }
+
+    /// Swap the [kube::Api] used for the lease, e.g. after credential rotation produced a new
+    /// client, keeping all configuration. Clones of the lock, observers and guards already held
+    /// switch too; renewals through [LeaseLock::with_renewal_client] keep their own client.
+    pub fn rebind(&self, api: Api) {
+        log::debug!("{}.rebind()", &self.client.lease_name);
+        *self.client.binding.lock().unwrap() = Binding {
+            api,
+            created: Default::default(),
+            cache: Default::default(),
+        };
+    }
+
+    /// Read-only view of the lease, sharing this lock's watch.
+    pub fn observe(&self) -> LeaseObserver {
+        LeaseObserver::from_client(self.client.clone())
+    }
+
+    /// Low-level client for the lease, sharing this lock's configuration.
+    pub fn lease_client(&self) -> LeaseClient {
+        LeaseClient::from_client(self.client.clone())
+    }
+
+    /// Acquire the lock; return [LeaseGuard] RAII object. Lease renewal will be done in background
+    /// as long as [LeaseGuard] exists.
+    ///
+    /// # Arguments
+    ///
+    /// `holder_id` - represents holder of the lock.
+    /// `acquire_timeout` - return [Error::AcquireTimeout] error if acquire did not complete within
+    /// the timeout.
+    pub async fn acquire(
+        &self,
+        holder_id: &str,
+        acquire_timeout: Option<Duration>,
+    ) -> Result<LeaseGuard, Error> {
+        self.acquire_with_priority(holder_id, acquire_timeout, Priority::Normal)
+            .await
+    }
+
+    /// Acquire the lock like [LeaseLock::acquire], giving up at `deadline` rather than after
+    /// a timeout, e.g. to share one deadline among several steps.
+    pub async fn acquire_until(
+        &self,
+        holder_id: &str,
+        deadline: Instant,
+    ) -> Result<LeaseGuard, Error> {
+        let acquire_timeout = deadline.saturating_duration_since(Instant::now());
+        self.acquire(holder_id, Some(acquire_timeout)).await
+    }
+
+    /// Acquire the lock like [LeaseLock::acquire], sharing the guard among tasks of this process
+    /// acquiring it for the same `holder_id`: concurrent calls are coalesced into one acquisition,
+    /// and a guard still held is handed out again. The lease is released when the last clone
+    /// of the guard is dropped.
+    pub async fn acquire_shared(
+        &self,
+        holder_id: &str,
+        acquire_timeout: Option<Duration>,
+    ) -> Result<Arc<LeaseGuard>, Error> {
+        let deadline = acquire_timeout.map(|to| Instant::now() + to);
+        let slot = self
+            .shared_guards
+            .lock()
+            .unwrap()
+            .entry(holder_id.to_string())
+            .or_default()
+            .clone();
+        let mut slot = match deadline {
+            None => slot.lock().await,
+            Some(d) => tokio::time::timeout_at(d.into(), slot.lock())
+                .await
+                .map_err(|_| Error::acquire_timeout(d))?,
+        };
+        if let Some(guard) = slot.upgrade().filter(|guard| guard.is_held()) {
+            log::debug!(
+                "{}.acquire_shared({}) => coalesced",
+                &self.client.lease_name,
+                holder_id
+            );
+            return Ok(guard);
+        }
+        let acquire_timeout = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+        let guard = Arc::new(self.acquire(holder_id, acquire_timeout).await?);
+        *slot = Arc::downgrade(&guard);
+        Ok(guard)
+    }
+
+    /// Acquire the lock like [LeaseLock::acquire], but when the lease frees up, let acquires
+    /// of this lock with higher `priority` in this process try first, e.g. so that critical
+    /// failover work goes before background chores gated by the same lock.
+    pub async fn acquire_with_priority(
+        &self,
+        holder_id: &str,
+        acquire_timeout: Option<Duration>,
+        priority: Priority,
+    ) -> Result<LeaseGuard, Error> {
+        self.client
+            .acquire(
+                holder_id,
+                acquire_timeout,
+                priority,
+                self.completion.token(),
             )
             .await
-            .map(LeaseState::try_from)?
     }
 
-    async fn get_state(&self) -> Result<LeaseState, Error> {
-        self.api
-            .get(&self.lease_name)
+    /// Resume holding a lease that `holder_id` still holds, e.g. after a restart of the holding
+    /// process, without a new acquisition. Return None if the lease isn't held by `holder_id`.
+    pub async fn reattach(&self, holder_id: &str) -> Result<Option<LeaseGuard>, Error> {
+        self.client
+            .reattach(holder_id, self.completion.token())
             .await
-            .map(LeaseState::try_from)?
     }
 
-    async fn wait_free(
+    /// Take over renewal of a lease held by another process, identified by a token from
+    /// [LeaseGuard::to_token], e.g. in a supervisor of a worker that died in a critical section.
+    /// Return None if the lease expired or was acquired anew since the token was issued.
+    /// The token's resourceVersion is not compared, as every renewal changes it.
+    pub async fn from_token(&self, token: &GuardToken) -> Result<Option<LeaseGuard>, Error> {
+        if token.lease_name != self.client.lease_name {
+            return Err(Error::Config(format!(
+                "token of lease {} used with lease {}",
+                &token.lease_name, &self.client.lease_name
+            )));
+        }
+        self.client
+            .reattach_as(&token.holder, token.acquire_time, self.completion.token())
+            .await
+    }
+
+    /// Start renewing the lease if it is held by `identity` as written to the lease, without
+    /// hashing it, see [crate::Renewer].
+    pub(crate) async fn adopt(&self, identity: &str) -> Result<Option<LeaseGuard>, Error> {
+        self.client
+            .reattach_as(identity, None, self.completion.token())
+            .await
+    }
+
+    /// Acquire the lease after a restart, guided by the state file (see
+    /// [LeaseLock::with_state_file]): reattach if the lease is still held by `holder_id` under the
+    /// recorded acquisition, wait for the cooldown first if it was lost while down, otherwise
+    /// contend right away.
+    /// Without a state file, this is [LeaseLock::acquire].
+    pub async fn resume(
         &self,
-        deadline: Option<Instant>,
-        holder: &str,
-    ) -> Result<LeaseState, Error> {
-        let mut lease_state = self.get_state().await?;
-        if lease_state.owner().is_none() {
-            return Ok(lease_state);
+        holder_id: &str,
+        acquire_timeout: Option<Duration>,
+    ) -> Result<LeaseGuard, Error> {
+        let state_file = match &self.client.state_file {
+            Some(state_file) => state_file,
+            None => return self.acquire(holder_id, acquire_timeout).await,
+        };
+        let identity = self.client.holder_identity(holder_id);
+        let persisted = state_file.load().filter(|persisted| {
+            persisted.held
+                && persisted.lease_name == self.client.lease_name
+                && persisted.holder == identity
+        });
+        let persisted = match persisted {
+            Some(persisted) => persisted,
+            None => return self.acquire(holder_id, acquire_timeout).await,
+        };
+        // Acquired anew since, e.g. by another process of the same identity: not ours to take.
+        if self.client.get_state().await?.transitions() == persisted.transitions {
+            if let Some(guard) = self.reattach(holder_id).await? {
+                return Ok(guard);
+            }
+        }
+        log::info!(
+            "{}.resume({}) => lost while down; cooldown({:?})",
+            &self.client.lease_name,
+            holder_id,
+            state_file.cooldown
+        );
+        let acquire_timeout = match acquire_timeout {
+            Some(to) if to <= state_file.cooldown => {
+                return Err(Error::AcquireTimeout {
+                    remaining: to,
+                    history: Vec::new(),
+                })
+            }
+            to => to.map(|to| to - state_file.cooldown),
+        };
+        tokio::time::sleep(state_file.cooldown).await;
+        self.acquire(holder_id, acquire_timeout).await
+    }
+
+    /// Release the lease if its holder's heartbeat (see [LeaseLock::with_heartbeat]) is older
+    /// than `max_silence`, even though the lease itself hasn't expired yet. Meant to be run
+    /// periodically by a janitor, so a crashed holder doesn't orphan a long lease for hours.
+    /// Return the released state if the lease was reaped.
+    pub async fn reap_orphaned(&self, max_silence: Duration) -> Result<Option<LeaseState>, Error> {
+        let lease_state = self.client.get_state().await?;
+        let silent = match (lease_state.owner(), lease_state.heartbeat) {
+            (Some(_), Some(heartbeat)) => {
+                heartbeat + chrono::Duration::from_std(max_silence).unwrap_or(chrono::Duration::MAX)
+                    <= chrono::Utc::now()
+            }
+            _ => false,
+        };
+        if !silent {
+            return Ok(None);
+        }
+        log::warn!(
+            "{}.reap_orphaned() => holder {:?} silent since {:?}; release",
+            &lease_state.lease_name,
+            &lease_state.holder,
+            &lease_state.heartbeat
+        );
+        self.client.release_lock(&lease_state).await.map(Some)
+    }
+
+    /// Find out whether `holder_id` was superseded: its lease expired and was taken over.
+    /// If so, return the current state, naming the new holder and the takeover.
+    pub async fn superseded(&self, holder_id: &str) -> Result<Option<LeaseState>, Error> {
+        let identity = self.client.holder_identity(holder_id);
+        let lease_state = self.client.get_state().await?;
+        match &lease_state.takeover {
+            Some(takeover) if takeover.previous_holder == identity => Ok(Some(lease_state)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Wait until the lease has no live holder, without acquiring it, e.g. for a lock held by
+    /// another component to clear. Return [Error::AcquireTimeout] if it is still held after
+    /// `timeout`.
+    pub async fn wait_until_free(&self, timeout: Option<Duration>) -> Result<LeaseState, Error> {
+        let deadline = timeout.map(|to| Instant::now() + to);
+        self.client
+            .wait_free(deadline, "", &mut Breadcrumbs::new(0))
+            .await
+    }
+
+    /// Acquire the lock if it can be done immediately. If not, return None.
+    /// Takes at most one read and one write of the lease, and never sleeps. With
+    /// [LeaseLock::with_create_if_missing], a missing lease is created already held by that write.
+    pub async fn try_acquire(&self, holder_id: &str) -> Result<Option<LeaseGuard>, Error> {
+        match self.try_acquire_state(holder_id).await? {
+            TryAcquire::Acquired(guard) => Ok(Some(guard)),
+            TryAcquire::Held(_) => Ok(None),
+        }
+    }
+
+    /// Like [LeaseLock::try_acquire], but tell who holds the lease if it can't be acquired,
+    /// e.g. to report "already running on ..." to the user.
+    pub async fn try_acquire_state(&self, holder_id: &str) -> Result<TryAcquire, Error> {
+        self.client
+            .try_acquire(holder_id, self.completion.token())
+            .await
+    }
+
+    /// Measure a few round trips to the API server by reading the lease, and return
+    /// [Error::UnsafeLeaseDuration] if the time left when a renewal is due isn't a safe multiple
+    /// of the slowest one, as renewals would risk landing after expiry. Meant to be called at
+    /// startup, to catch dangerous configurations before they flap. Return the slowest round trip.
+    pub async fn check_round_trips(&self) -> Result<Duration, Error> {
+        let mut rtt = Duration::ZERO;
+        for _ in 0..RTT_SAMPLES {
+            let started = Instant::now();
+            match self.client.api().get(&self.client.lease_name).await {
+                Ok(_) => {}
+                Err(kube::Error::Api(api_err)) if api_err.code == StatusCode::NOT_FOUND => {}
+                Err(e) => return Err(e.into()),
+            }
+            rtt = rtt.max(started.elapsed());
         }
+        let margin = self
+            .client
+            .lease_duration
+            .saturating_sub(self.client.renew_interval());
+        log::debug!(
+            "{}.check_round_trips() => rtt({:?}), margin({:?})",
+            &self.client.lease_name,
+            rtt,
+            margin
+        );
+        if margin < rtt * SAFE_RTT_MULTIPLE {
+            return Err(Error::UnsafeLeaseDuration { margin, rtt });
+        }
+        Ok(rtt)
+    }
 
-        for backoff in self.expo.clone() {
-            if let Some(d) = deadline {
-                if Instant::now() + backoff >= d {
-                    return Err(Error::AcquireTimeout);
+    /// Dry-run the patch acquiring the lease as `holder_id`, without changing the lease, so that
+    /// admission webhook rejections or RBAC gaps surface at startup rather than at the first
+    /// failover. Should the lease keep changing under the dry-run, the conflict is returned after
+    /// [LeaseLock::with_stale_retries] retries.
+    pub async fn verify(&self, holder_id: &str) -> Result<(), Error> {
+        let identity = self.client.holder_identity(holder_id);
+        let mut backoff = self.client.expo.clone();
+        let mut retries = 0;
+        loop {
+            let lease_state = self.client.get_state().await?;
+            let patch = self.client.overwrite_patch(&identity, &lease_state)?;
+            let params = PatchParams {
+                dry_run: true,
+                ..PatchParams::apply(&self.client.field_manager).force()
+            };
+            match self
+                .client
+                .api()
+                .patch(
+                    &self.client.lease_name,
+                    &params,
+                    &kube::api::Patch::Apply(&patch),
+                )
+                .await
+            {
+                Ok(_) => {
+                    log::debug!("{}.verify({}) => ok", &self.client.lease_name, &identity);
+                    return Ok(());
+                }
+                // the lease changed since read: not a verdict on the patch, try again
+                Err(kube::Error::Api(api_err))
+                    if api_err.code == StatusCode::CONFLICT
+                        && retries < self.client.stale_retries =>
+                {
+                    retries += 1;
+                    tokio::time::sleep(backoff.next().expect("infinite backoff")).await;
+                }
+                Err(e) => {
+                    log::warn!("{}.verify({}) => {}", &self.client.lease_name, &identity, e);
+                    return Err(e.into());
                 }
             }
+        }
+    }
+}
+
+impl LeaseLockClient {
+    /// Next delay of `backoff`, or [Error::AcquireTimeout] if sleeping it would cross `deadline`.
+    fn next_backoff(
+        &self,
+        backoff: &mut ExponentialBackoff,
+        deadline: Option<Instant>,
+    ) -> Result<Duration, Error> {
+        let delay = self.jittered(backoff.next().expect("infinite backoff"));
+        match deadline {
+            Some(d) if Instant::now() + delay >= d => Err(Error::acquire_timeout(d)),
+            _ => Ok(delay),
+        }
+    }
+
+    fn jittered(&self, delay: Duration) -> Duration {
+        match &self.jitter {
+            None => delay,
+            Some(random) => {
+                let half = delay / 2;
+                half + Duration::from_nanos(random.gen_range(half.as_nanos() as u64))
+            }
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                lease = %self.lease_name,
+                holder = holder_id,
+                resource_version = tracing::field::Empty,
+                correlation_id = tracing::field::Empty
+            )
+        )
+    )]
+    pub async fn acquire(
+        &self,
+        holder_id: &str,
+        acquire_timeout: Option<Duration>,
+        priority: Priority,
+        completion_tx: Sender<()>,
+    ) -> Result<LeaseGuard, Error> {
+        log::debug!(
+            "{}.acquire({}, {:?})",
+            &self.lease_name,
+            holder_id,
+            acquire_timeout
+        );
 
+        self.validate()?;
+        if self.auto_create {
+            self.create_if_missing(&Default::default()).await?;
+        }
+        let _waiter = self.contention.enter(priority);
+        let identity = self.holder_identity(holder_id);
+        if identity != holder_id {
             log::debug!(
-                "{}.wait_free({}) => {}:backoff({:?})!",
+                "{}.acquire({}) => written as {}",
                 &self.lease_name,
-                holder,
-                &lease_state.holder.unwrap(),
-                backoff
+                holder_id,
+                &identity
             );
-            tokio::time::sleep(backoff).await;
-
-            lease_state = self.get_state().await?;
-            if lease_state.owner().is_none() {
-                return Ok(lease_state);
-            }
         }
 
-        panic!("impossible");
+        #[cfg(feature = "debug-lock-order")]
+        lock_order::check_acquire(&identity, &self.lease_name);
+
+        if let Some(guard) = self
+            .resume_debounced(holder_id, &identity, &completion_tx)
+            .await?
+        {
+            return Ok(guard);
+        }
+
+        let deadline = acquire_timeout.map(|to| Instant::now() + to);
+        let mut retry_backoff = self.expo.clone();
+        let mut failures = 0;
+        let mut failing_since = Instant::now();
+        let mut breadcrumbs = Breadcrumbs::new(self.timeout_history);
+
+        loop {
+            let contended = self
+                .contend(
+                    holder_id,
+                    &identity,
+                    priority,
+                    deadline,
+                    &mut retry_backoff,
+                    &mut breadcrumbs,
+                )
+                .await;
+            let lease_state = match contended {
+                Ok(Some(lease_state)) => lease_state,
+                Ok(None) => {
+                    failures = 0;
+                    continue;
+                }
+                Err(Error::AcquireTimeout { remaining, .. }) => {
+                    return Err(Error::AcquireTimeout {
+                        remaining,
+                        history: breadcrumbs.crumbs.into(),
+                    })
+                }
+                Err(e) => {
+                    if failures == 0 {
+                        failing_since = Instant::now();
+                    }
+                    failures += 1;
+                    match self.retry_decision(Operation::Acquire, &e, failures, failing_since) {
+                        Some(RetryDecision::Retry(after))
+                            if deadline.is_none_or(|d| Instant::now() + after < d) =>
+                        {
+                            tokio::time::sleep(after).await;
+                            continue;
+                        }
+                        _ => return Err(e),
+                    }
+                }
+            };
+            return self
+                .finish_acquire(lease_state, holder_id, &identity, completion_tx)
+                .await;
+        }
+    }
+
+    /// Acquire the lease if it is free right now, with a single read and a single write and no
+    /// backoff. Otherwise, return its state as read, naming the current holder. Unlike
+    /// [LeaseLockClient::acquire], the write is neither re-read nor retried on conflict: the apply
+    /// carries the resourceVersion read, so its response is authoritative.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                lease = %self.lease_name,
+                holder = holder_id,
+                resource_version = tracing::field::Empty,
+                correlation_id = tracing::field::Empty
+            )
+        )
+    )]
+    pub(crate) async fn try_acquire(
+        &self,
+        holder_id: &str,
+        completion_tx: Sender<()>,
+    ) -> Result<TryAcquire, Error> {
+        self.validate()?;
+        let identity = self.holder_identity(holder_id);
+
+        #[cfg(feature = "debug-lock-order")]
+        lock_order::check_acquire(&identity, &self.lease_name);
+
+        if let Some(guard) = self
+            .resume_debounced(holder_id, &identity, &completion_tx)
+            .await?
+        {
+            return Ok(TryAcquire::Acquired(guard));
+        }
+
+        let (lease_state, missing) = match self.api().get(&self.lease_name).await {
+            Ok(lease_obj) => (LeaseState::try_from(lease_obj)?, false),
+            Err(kube::Error::Api(api_err))
+                if api_err.code == StatusCode::NOT_FOUND && self.auto_create =>
+            {
+                (self.missing_state()?, true)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if lease_state.owner().is_some() || self.contention.outranked(Priority::Normal) {
+            log::debug!(
+                "{}.try_acquire({}) => held by {:?}",
+                &self.lease_name,
+                holder_id,
+                lease_state.owner()
+            );
+            return Ok(TryAcquire::Held(lease_state));
+        }
+        if let Some(hook) = &self.pre_acquire_hook {
+            if hook(&lease_state).await == Decision::Veto {
+                log::debug!("{}.try_acquire({}) => vetoed", &self.lease_name, holder_id);
+                return Ok(TryAcquire::Held(lease_state));
+            }
+        }
+        let mut patch = self.overwrite_patch(&identity, &lease_state)?;
+        let patch_res = if missing {
+            // create the lease held right away, rather than creating it and then acquiring it
+            if let Some(template) = &self.lease_template {
+                template(&mut patch);
+            }
+            self.api().create(&PostParams::default(), &patch).await
+        } else {
+            self.api()
+                .patch(
+                    &self.lease_name,
+                    &PatchParams::apply(&self.field_manager).force(),
+                    &kube::api::Patch::Apply(&patch),
+                )
+                .await
+        };
+        let acquired = match patch_res {
+            Ok(lease_obj) => {
+                if missing {
+                    log::debug!("{}.try_acquire({}) => created", &self.lease_name, holder_id);
+                    let _ = self.created().set(());
+                }
+                self.taken_over(lease_obj, &lease_state)?
+            }
+            // a conflicting create means the lease was created by someone else in between
+            Err(kube::Error::Api(api_err)) if api_err.code == StatusCode::CONFLICT => {
+                log::debug!(
+                    "{}.try_acquire({}) => conflict",
+                    &self.lease_name,
+                    holder_id
+                );
+                self.record_conflict();
+                return Ok(TryAcquire::Held(lease_state));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        self.finish_acquire(acquired, holder_id, &identity, completion_tx)
+            .await
+            .map(TryAcquire::Acquired)
+    }
+
+    /// Take back the lease of a guard of `identity` dropped within the release debounce, if any,
+    /// cancelling its release. See [LeaseLock::with_release_debounce].
+    async fn resume_debounced(
+        &self,
+        holder_id: &str,
+        identity: &str,
+        completion_tx: &Sender<()>,
+    ) -> Result<Option<LeaseGuard>, Error> {
+        let debounced = match self.debounced.lock().unwrap().remove(identity) {
+            Some(debounced) => debounced,
+            None => return Ok(None),
+        };
+        debounced.abort_handle.abort();
+        if debounced.renewed.elapsed() >= self.debounce_margin() {
+            // too close to expiry to renew in time: release now and acquire as usual
+            log::debug!(
+                "{}.acquire({}) => release of {} too late to cancel",
+                &self.lease_name,
+                holder_id,
+                &debounced.correlation_id
+            );
+            let _ = self
+                .release_held(
+                    &debounced.lease_state,
+                    &debounced.fence,
+                    None,
+                    &debounced.correlation_id,
+                )
+                .await;
+            return Ok(None);
+        }
+        log::debug!(
+            "{}.acquire({}) => release of {} cancelled",
+            &self.lease_name,
+            holder_id,
+            &debounced.correlation_id
+        );
+        debounced.fence.release_all().await;
+        let guard = self.new_guard(
+            debounced.lease_state,
+            identity,
+            completion_tx.clone(),
+            debounced.renewed,
+        );
+        guard.fence.acquire_all(&guard.lease_state).await?;
+        metrics::record(&self.lease_name, |m| m.acquires += 1);
+        Ok(Some(guard))
+    }
+
+    /// Set up the guard of the lease just written as `identity`.
+    async fn finish_acquire(
+        &self,
+        lease_state: LeaseState,
+        holder_id: &str,
+        identity: &str,
+        completion_tx: Sender<()>,
+    ) -> Result<LeaseGuard, Error> {
+        let guard = self.new_guard(lease_state, identity, completion_tx, Instant::now());
+        // recorded on the span of acquire or try_acquire
+        #[cfg(feature = "tracing")]
+        tracing::Span::current()
+            .record(
+                "resource_version",
+                guard.lease_state.resource_version.as_str(),
+            )
+            .record("correlation_id", guard.correlation_id());
+        log::debug!(
+            "{}.acquire({}) => correlation id {}",
+            &self.lease_name,
+            holder_id,
+            guard.correlation_id()
+        );
+        guard.fence.acquire_all(&guard.lease_state).await?;
+        if let Some(warm_up) = &self.warm_up {
+            let ready = (warm_up.hook)(&guard.lease_state);
+            if tokio::time::timeout(warm_up.timeout, ready).await.is_err() {
+                log::warn!(
+                    "{}.acquire({}) => warm-up timeout; release",
+                    &self.lease_name,
+                    holder_id
+                );
+                return Err(Error::WarmUpTimeout);
+            }
+        }
+        if let Some(state_file) = &self.state_file {
+            state_file.store(&guard.lease_state, true);
+        }
+        metrics::record(&self.lease_name, |m| m.acquires += 1);
+        #[cfg(feature = "otel")]
+        otel::acquired(&self.lease_name, identity, guard.correlation_id());
+        if let Some(reporter) = &self.condition_reporter {
+            reporter.report_logged(&guard.lease_state).await;
+        }
+        Ok(guard)
+    }
+
+    /// One round of contention for the lease. Return its state if we took it over,
+    /// None if we should contend again.
+    async fn contend(
+        &self,
+        holder_id: &str,
+        identity: &str,
+        priority: Priority,
+        deadline: Option<Instant>,
+        retry_backoff: &mut ExponentialBackoff,
+        breadcrumbs: &mut Breadcrumbs,
+    ) -> Result<Option<LeaseState>, Error> {
+        let lease_state = self.wait_free(deadline, holder_id, breadcrumbs).await?;
+        if self.contention.outranked(priority) {
+            let backoff = self.next_backoff(retry_backoff, deadline)?;
+            log::debug!(
+                "{}.acquire({}) => outranked:backoff({:?})",
+                &self.lease_name,
+                holder_id,
+                backoff
+            );
+            tokio::time::sleep(backoff).await;
+            return Ok(None);
+        }
+        let lease_state = match self.takeover_delay(&lease_state, identity, deadline) {
+            None => lease_state,
+            Some((delay, reason)) => {
+                log::debug!(
+                    "{}.acquire({}) => {}:defer({:?})",
+                    &self.lease_name,
+                    holder_id,
+                    reason,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                let fresh = self.get_state().await?;
+                if fresh.owner().is_some() {
+                    return Ok(None);
+                }
+                fresh
+            }
+        };
+        if let Some(hook) = &self.pre_acquire_hook {
+            if hook(&lease_state).await == Decision::Veto {
+                let backoff = self.next_backoff(retry_backoff, deadline)?;
+                log::debug!(
+                    "{}.acquire({}) => vetoed:backoff({:?})",
+                    &self.lease_name,
+                    holder_id,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                return Ok(None);
+            }
+        }
+        let lease_state = if self.strict_takeover {
+            let fresh = self.get_state().await?;
+            if matches!(fresh.owner(), Some(owner) if owner != identity) {
+                self.record_conflict();
+                let backoff = self.next_backoff(retry_backoff, deadline)?;
+                log::debug!(
+                    "{}.acquire({}) => strict:held by {:?}:backoff({:?})",
+                    &self.lease_name,
+                    holder_id,
+                    fresh.owner(),
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                return Ok(None);
+            }
+            fresh
+        } else {
+            lease_state
+        };
+        let lease_state = self.try_overwrite(identity, lease_state).await?;
+        if lease_state.owner() != Some(identity) {
+            self.record_conflict();
+            return Ok(None);
+        }
+        if self.verify_write || self.strict_takeover {
+            let observed = self.get_state().await?;
+            if observed.holder() != Some(identity)
+                || observed.acquire_time != lease_state.acquire_time
+            {
+                // Our write was overtaken: the one whose write the server kept wins,
+                // everybody else backs off before contending again.
+                self.record_conflict();
+                let backoff = self.next_backoff(retry_backoff, deadline)?;
+                log::debug!(
+                    "{}.acquire({}) => overtaken by {:?}:backoff({:?})",
+                    &self.lease_name,
+                    holder_id,
+                    observed.holder(),
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                return Ok(None);
+            }
+        }
+        Ok(Some(lease_state))
+    }
+
+    /// Delay to defer taking over the free `lease_state` by, and why: its last holder ran in our
+    /// zone, or it expired just now and may be restarting to reclaim it. No delay if it would
+    /// cross `deadline`.
+    fn takeover_delay(
+        &self,
+        lease_state: &LeaseState,
+        identity: &str,
+        deadline: Option<Instant>,
+    ) -> Option<(Duration, &'static str)> {
+        let last_zone = lease_state.annotations.get(HOLDER_ZONE_ANNOTATION);
+        let same_zone = match (self.zone_spread_delay, &self.zone) {
+            (Some(delay), Some(zone)) if last_zone == Some(zone) => {
+                Some((delay, "same zone as last holder"))
+            }
+            _ => None,
+        };
+        let expired_for = chrono::Utc::now() - lease_state.expires_at();
+        let incumbent = match (self.incumbent_grace, lease_state.holder()) {
+            (Some(grace), Some(holder))
+                if holder != identity && expired_for < lease_state.lease_duration() =>
+            {
+                Some((grace, "incumbent may reclaim"))
+            }
+            _ => None,
+        };
+        let (delay, reason) = same_zone
+            .into_iter()
+            .chain(incumbent)
+            .max_by_key(|(d, _)| *d)?;
+        match deadline {
+            Some(d) if Instant::now() + delay >= d => None,
+            _ => Some((delay, reason)),
+        }
+    }
+
+    /// Current time by the configured [TimeSource], and the source actually used.
+    fn now(&self) -> (UtcInstant, TimeSource) {
+        let local = chrono::Utc::now();
+        match (self.time_source, *self.server_offset.lock().unwrap()) {
+            (TimeSource::Server, Some(offset)) => (local + offset, TimeSource::Server),
+            _ => (local, TimeSource::Local),
+        }
+    }
+
+    /// Estimate the server clock offset from the server's timestamp of our write of `lease_obj`.
+    fn observe_server_time(&self, lease_obj: &LeaseObject) {
+        if self.time_source != TimeSource::Server {
+            return;
+        }
+        let written = lease_obj
+            .metadata
+            .managed_fields
+            .iter()
+            .flatten()
+            .filter(|entry| entry.manager.as_deref() == Some(self.field_manager.as_str()))
+            .filter_map(|entry| entry.time.as_ref())
+            .map(|time| time.0)
+            .max();
+        if let Some(written) = written {
+            *self.server_offset.lock().unwrap() = Some(written - chrono::Utc::now());
+        }
+    }
+
+    fn record_conflict(&self) {
+        self.contention.conflict();
+        metrics::record(&self.lease_name, |m| m.conflicts += 1);
+    }
+
+    fn observe_error(&self, operation: Operation, error: &Error) {
+        if let Some(observer) = &self.error_observer {
+            observer(error, operation);
+        }
+    }
+
+    /// Report the `attempt`th failure of `operation` since `since` to the error observer and
+    /// ask the retry policy, if any, what to do about it.
+    fn retry_decision(
+        &self,
+        operation: Operation,
+        error: &Error,
+        attempt: u32,
+        since: Instant,
+    ) -> Option<RetryDecision> {
+        self.observe_error(operation, error);
+        let policy = self.retry_policy.as_ref()?;
+        let decision = policy.on_error(operation, error, attempt, since.elapsed());
+        log::debug!(
+            "{}.retry({:?}, {}) => {} => {:?}",
+            &self.lease_name,
+            operation,
+            attempt,
+            error,
+            decision
+        );
+        Some(decision)
+    }
+
+    async fn reattach(
+        &self,
+        holder_id: &str,
+        completion_tx: Sender<()>,
+    ) -> Result<Option<LeaseGuard>, Error> {
+        let identity = self.holder_identity(holder_id);
+        self.reattach_as(&identity, None, completion_tx).await
+    }
+
+    /// Reattach to the lease if it is held by `identity` as written to the lease and, if given,
+    /// since `acquire_time`.
+    async fn reattach_as(
+        &self,
+        identity: &str,
+        acquire_time: Option<UtcInstant>,
+        completion_tx: Sender<()>,
+    ) -> Result<Option<LeaseGuard>, Error> {
+        self.validate()?;
+        let lease_state = self.get_state().await?;
+        if lease_state.owner() != Some(identity) {
+            log::debug!(
+                "{}.reattach({}) => held by {:?}",
+                &self.lease_name,
+                identity,
+                lease_state.owner()
+            );
+            return Ok(None);
+        }
+        if acquire_time.is_some_and(|t| lease_state.acquire_time != Some(t)) {
+            log::debug!(
+                "{}.reattach({}) => re-acquired at {:?}",
+                &self.lease_name,
+                identity,
+                lease_state.acquire_time
+            );
+            return Ok(None);
+        }
+        let lease_state = self.renew_lease(lease_state, true).await?;
+        log::debug!("{}.reattach({}) => OK", &self.lease_name, identity);
+        let guard = self.new_guard(lease_state, identity, completion_tx, Instant::now());
+        guard.fence.acquire_all(&guard.lease_state).await?;
+        if let Some(state_file) = &self.state_file {
+            state_file.store(&guard.lease_state, true);
+        }
+        Ok(Some(guard))
+    }
+
+    fn new_guard(
+        &self,
+        lease_state: LeaseState,
+        identity: &str,
+        completion_tx: Sender<()>,
+        renewed: Instant,
+    ) -> LeaseGuard {
+        #[cfg(feature = "debug-lock-order")]
+        lock_order::acquired(identity, &self.lease_name);
+        let correlation_id = format!("{:016x}", rand::random::<u64>());
+        metrics::record(&self.lease_name, |m| {
+            m.held += 1;
+            m.correlation_ids.insert(correlation_id.clone());
+        });
+        let fence = Arc::new(Fence::new(self.fenced_resources.clone()));
+        let renewal = Arc::new(RenewalControl::default());
+        let registration = registry::register(HeldInfo {
+            lease_name: self.lease_name.clone(),
+            holder: identity.to_string(),
+            acquire_time: lease_state.acquire_time,
+            held_since: Instant::now(),
+            correlation_id: correlation_id.clone(),
+        });
+        let ownership = Arc::new(Ownership::new(registration, correlation_id, renewed));
+        let abort_handle = self.clone().schedule_renewal(
+            lease_state.clone(),
+            identity.to_string(),
+            fence.clone(),
+            renewal.clone(),
+            ownership.clone(),
+        );
+        LeaseGuard {
+            stopped: false,
+            released: false,
+            client: self.clone(),
+            lease_state,
+            abort_handle,
+            completion_tx,
+            fence,
+            renewal,
+            ownership,
+        }
+    }
+
+    /// Annotations this manager keeps on a held lease.
+    fn annotations(
+        &self,
+        now: UtcInstant,
+        status: Status<'_>,
+        takeover: Option<&Takeover>,
+    ) -> serde_json::Value {
+        if self.spec_only_apply {
+            return serde_json::json!({});
+        }
+        let mut annotations = self.audit_annotations.clone();
+        if self.status_annotations {
+            annotations.insert(HELD_BY_ANNOTATION.into(), status.holder.into());
+            annotations.insert(
+                EXPIRES_AT_ANNOTATION.into(),
+                (status.renew_time + self.lease_duration_chrono())
+                    .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            );
+            annotations.insert(RENEWALS_ANNOTATION.into(), status.renewals.to_string());
+        }
+        if let Some(zone) = &self.zone {
+            annotations.insert(HOLDER_ZONE_ANNOTATION.into(), zone.clone());
+        }
+        if self.heartbeat_interval.is_some() {
+            annotations.insert(
+                HEARTBEAT_ANNOTATION.into(),
+                now.to_rfc3339_opts(chrono::SecondsFormat::Micros, false),
+            );
+        }
+        if let Some(takeover) = takeover {
+            annotations.insert(
+                PREVIOUS_HOLDER_ANNOTATION.into(),
+                takeover.previous_holder.clone(),
+            );
+            annotations.insert(
+                TAKEN_OVER_AT_ANNOTATION.into(),
+                takeover
+                    .at
+                    .to_rfc3339_opts(chrono::SecondsFormat::Micros, false),
+            );
+        }
+        schema::downgrade(&mut annotations, self.schema_version);
+        serde_json::json!(annotations)
+    }
+
+    fn lease_duration_chrono(&self) -> chrono::Duration {
+        chrono::Duration::from_std(self.lease_duration).unwrap_or(chrono::Duration::MAX)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.drained.load(Ordering::SeqCst) {
+            return Err(Error::Drained);
+        }
+        validate_renewal(self.lease_duration, self.renew_interval)?;
+        if let Some(debounce) = self.release_debounce {
+            let margin = self.debounce_margin();
+            if debounce >= margin {
+                return Err(Error::Config(format!(
+                    "release debounce {:?} is not within {:?} left after renew interval",
+                    debounce, margin
+                )));
+            }
+        }
+        if self.spec_only_apply
+            && (self.status_annotations
+                || self.heartbeat_interval.is_some()
+                || self.zone.is_some()
+                || !self.audit_annotations.is_empty())
+        {
+            return Err(Error::Config(
+                "spec-only apply excludes status, heartbeat, zone and audit annotations".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn renew_interval(&self) -> Duration {
+        renew_interval(self.lease_duration, self.renew_interval)
+    }
+
+    /// Time left on the lease once a renewal is due, which a debounced release must fit in to be
+    /// taken back safely.
+    fn debounce_margin(&self) -> Duration {
+        self.lease_duration.saturating_sub(self.renew_interval())
+    }
+
+    /// Fraction of the lease duration left when a renewal is due, in thousandths if the renew
+    /// interval was configured.
+    fn renewal_slack(&self) -> (u32, u32) {
+        match self.renew_interval {
+            None => RENEWAL_SLACK,
+            Some(interval) => {
+                let left = self.lease_duration.saturating_sub(interval).as_secs_f64()
+                    / self.lease_duration.as_secs_f64();
+                (((left * 1000.0) as u32).max(1), 1000)
+            }
+        }
+    }
+
+    /// `leaseDurationSeconds` to write: the lease duration rounded up to whole seconds.
+    fn lease_duration_seconds(&self) -> Result<i32, Error> {
+        let secs = self.lease_duration.as_millis().div_ceil(1000);
+        Ok(i32::try_from(secs)?)
+    }
+
+    pub(crate) fn watch(&self) -> impl Stream<Item = LeaseState> + Send + 'static {
+        match &self.bulk_watch {
+            Some(bulk_watch) => bulk_watch.subscribe(self.lease_name.clone()).boxed(),
+            None => watch_lease(self.api(), self.lease_name.clone(), self.expo.clone()).boxed(),
+        }
+    }
+
+    /// State of the lease to poll while waiting: the latest seen by the bulk watch, if any,
+    /// otherwise read from the API.
+    async fn poll_state(&self) -> Result<LeaseState, Error> {
+        if self.bulk_watch.is_none() {
+            return self.get_state().await;
+        }
+        let cache = self.cache();
+        cache.start(&self.lease_name, || self.watch());
+        if let Some(lease_state) = cache.get(Duration::MAX) {
+            return Ok(lease_state);
+        }
+        let lease_state = self.get_state().await?;
+        cache.put(lease_state.clone());
+        Ok(lease_state)
+    }
+
+    /// Create the lease object with `labels` unless it exists already.
+    /// Concurrent calls on clones of this lock share a single attempt, and replicas finding the
+    /// lease missing spread their creates randomly, so mass restarts don't stampede the API server.
+    pub(crate) async fn create_if_missing(
+        &self,
+        labels: &BTreeMap<String, String>,
+    ) -> Result<(), Error> {
+        self.created()
+            .get_or_try_init(|| self.create_lease(labels))
+            .await
+            .map(|_| ())
+    }
+
+    /// The state of a lease that does not exist yet, as if it was created empty.
+    fn missing_state(&self) -> Result<LeaseState, Error> {
+        LeaseState::try_from(LeaseObject {
+            metadata: kube::api::ObjectMeta {
+                name: Some(self.lease_name.clone()),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    async fn create_lease(&self, labels: &BTreeMap<String, String>) -> Result<(), Error> {
+        match self.api().get(&self.lease_name).await {
+            Ok(_) => return Ok(()),
+            Err(kube::Error::Api(api_err)) if api_err.code == StatusCode::NOT_FOUND => {}
+            Err(e) => return Err(e.into()),
+        }
+        let spread = CREATE_SPREAD.as_nanos() as u64;
+        let delay = Duration::from_nanos(match &self.jitter {
+            Some(random) => random.gen_range(spread),
+            None => ThreadRandom.gen_range(spread),
+        });
+        log::debug!(
+            "{}.create_if_missing() => missing:delay({:?})",
+            &self.lease_name,
+            delay
+        );
+        tokio::time::sleep(delay).await;
+
+        let mut lease: LeaseObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "coordination.k8s.io/v1",
+            "kind": "Lease",
+            "metadata": { "name": &self.lease_name, "labels": labels },
+            "spec": {},
+        }))?;
+        if let Some(template) = &self.lease_template {
+            template(&mut lease);
+        }
+        match self.api().create(&PostParams::default(), &lease).await {
+            Ok(_) => {
+                log::debug!("{}.create_if_missing() => created", &self.lease_name);
+                Ok(())
+            }
+            Err(kube::Error::Api(api_err)) if api_err.code == StatusCode::CONFLICT => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Set (or remove, if `value` is None) an annotation on the lease, leaving everything else
+    /// intact.
+    pub(crate) async fn annotate(
+        &self,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<LeaseState, Error> {
+        let patch = serde_json::json!({
+            "metadata": { "annotations": { key: value } },
+        });
+        let params = PatchParams {
+            field_manager: Some(self.field_manager.clone()),
+            ..Default::default()
+        };
+        self.api()
+            .patch(&self.lease_name, &params, &kube::api::Patch::Merge(&patch))
+            .await
+            .map(LeaseState::try_from)?
+    }
+
+    fn namespace(&self) -> Option<String> {
+        let api = self.api();
+        let (_, rest) = api.resource_url().split_once("/namespaces/")?;
+        rest.split('/').next().map(str::to_string)
+    }
+
+    fn api(&self) -> Api {
+        self.binding.lock().unwrap().api.clone()
+    }
+
+    fn cache(&self) -> Arc<LeaseCache> {
+        self.binding.lock().unwrap().cache.clone()
+    }
+
+    fn created(&self) -> Arc<tokio::sync::OnceCell<()>> {
+        self.binding.lock().unwrap().created.clone()
+    }
+
+    pub(crate) fn holder_identity(&self, holder_id: &str) -> String {
+        match &self.holder_salt {
+            None => holder_id.to_string(),
+            Some(salt) => {
+                let mut hasher = Sha256::new();
+                hasher.update(salt.as_bytes());
+                hasher.update(holder_id.as_bytes());
+                format!("{:x}", hasher.finalize())
+            }
+        }
+    }
+
+    pub(crate) async fn release_lock(&self, lease_state: &LeaseState) -> Result<LeaseState, Error> {
+        self.release_lock_with(lease_state, None).await
+    }
+
+    /// Release the lease held as of `lease_state`, leaving `handover` for the next holder.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                lease = %self.lease_name,
+                holder = ?lease_state.holder,
+                resource_version = %lease_state.resource_version,
+                handover = handover.is_some()
+            )
+        )
+    )]
+    async fn release_lock_with(
+        &self,
+        lease_state: &LeaseState,
+        handover: Option<&str>,
+    ) -> Result<LeaseState, Error> {
+        let mut retries = 0;
+        loop {
+            // Release only if still held by the same acquisition, as of a fresh read: a slow
+            // release must not evict a new holder.
+            let current = self.get_state().await?;
+            if current.holder != lease_state.holder
+                || current.acquire_time != lease_state.acquire_time
+            {
+                log::debug!(
+                    "{}.release_lock({:?}) => held by {:?} since {:?}; skip",
+                    &lease_state.lease_name,
+                    &lease_state.holder,
+                    &current.holder,
+                    &current.acquire_time
+                );
+                return Ok(current);
+            }
+            match self.release_patch(&current, handover).await {
+                Err(Error::Kube(kube::Error::Api(api_err)))
+                    if api_err.code == StatusCode::CONFLICT && retries < self.stale_retries =>
+                {
+                    retries += 1;
+                }
+                released => return released,
+            }
+        }
+    }
+
+    /// Clear the holder of the lease last seen as `lease_state`, unless it changed since.
+    pub(crate) async fn release_patch(
+        &self,
+        lease_state: &LeaseState,
+        handover: Option<&str>,
+    ) -> Result<LeaseState, Error> {
+        // Keep the zone of the last holder for successors, see LeaseLock::with_zone_spread.
+        let annotations: BTreeMap<_, _> = self
+            .zone
+            .as_deref()
+            .map(|zone| (HOLDER_ZONE_ANNOTATION, zone))
+            .into_iter()
+            .chain(handover.map(|payload| (HANDOVER_ANNOTATION, payload)))
+            .collect();
+        let patch: LeaseObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "coordination.k8s.io/v1",
+            "kind": "Lease",
+            "metadata": {
+                "name": &lease_state.lease_name,
+                "resourceVersion": &lease_state.resource_version,
+                "annotations": annotations,
+            },
+            "spec": {
+                "holderIdentity": serde_json::json!(null),
+                "leaseTransitions": lease_state.transitions,
+            }
+        }))?;
+
+        self.api()
+            .patch(
+                &lease_state.lease_name,
+                &PatchParams::apply(&self.field_manager).force(),
+                &kube::api::Patch::Apply(&patch),
+            )
+            .await
+            .map(LeaseState::try_from)?
+    }
+
+    /// Release the lease held as `lease_state` once `fence` is released, retrying as the retry
+    /// policy says.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(lease = %self.lease_name, correlation_id = correlation_id)
+        )
+    )]
+    async fn release_held(
+        &self,
+        lease_state: &LeaseState,
+        fence: &Fence,
+        handover: Option<&str>,
+        correlation_id: &str,
+    ) -> Result<LeaseState, Error> {
+        fence.release_all().await;
+        let started = Instant::now();
+        let mut attempt = 0;
+        let released = loop {
+            match self.release_lock_with(lease_state, handover).await {
+                Err(e) => {
+                    attempt += 1;
+                    match self.retry_decision(Operation::Release, &e, attempt, started) {
+                        Some(RetryDecision::Retry(after)) => tokio::time::sleep(after).await,
+                        _ => break Err(e),
+                    }
+                }
+                Ok(released) => break Ok(released),
+            }
+        };
+        match &released {
+            Err(e) => log::error!(
+                "{}.release_lock({:?}#{}) => {}",
+                &lease_state.lease_name,
+                &lease_state.holder,
+                correlation_id,
+                e
+            ),
+            Ok(released) => {
+                log::debug!(
+                    "release_lock({}, {:?}#{}) => OK",
+                    &lease_state.lease_name,
+                    &lease_state.holder,
+                    correlation_id
+                );
+                if let Some(state_file) = &self.state_file {
+                    state_file.store(lease_state, false);
+                }
+                if let Some(reporter) = &self.condition_reporter {
+                    reporter.report_logged(released).await;
+                }
+            }
+        }
+        released
+    }
+
+    #[must_use]
+    fn schedule_renewal(
+        mut self,
+        lease_state: LeaseState,
+        holder_id: String,
+        fence: Arc<Fence>,
+        control: Arc<RenewalControl>,
+        ownership: Arc<Ownership>,
+    ) -> AbortHandle {
+        if let Some(client) = self.renewal_client.take() {
+            let api = match self.namespace() {
+                Some(namespace) => kube::Api::namespaced(client, &namespace),
+                None => kube::Api::all(client),
+            };
+            let binding = self.binding.lock().unwrap().clone();
+            self.binding = Arc::new(Mutex::new(Binding { api, ..binding }));
+        }
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "renewal",
+            lease = %self.lease_name,
+            holder = %holder_id,
+            correlation_id = %ownership.correlation_id
+        );
+        let (abort_handle, abort_reg) = AbortHandle::new_pair();
+        let task_name = format!("lease-renew:{}", &self.lease_name);
+        let runtime = if self.dedicated_renewal_runtime {
+            Some(&*RENEWAL_RUNTIME)
+        } else {
+            None
+        };
+        let renewal = Abortable::new(
+            async move {
+                // holder and correlation id, to tell stints of the same holder apart in logs
+                let tag = format!("{}#{}", holder_id, ownership.correlation_id);
+                let renew_interval = self.renew_interval();
+                let tick = self
+                    .heartbeat_interval
+                    .map_or(renew_interval, |h| h.min(renew_interval));
+                let renewed = ownership.renewed_tx.borrow().unwrap_or_else(Instant::now);
+                let mut next_renewal = renewed + renew_interval;
+                let mut delay = tick.min(next_renewal.saturating_duration_since(Instant::now()));
+                let mut failures = 0;
+                let mut failing_since = Instant::now();
+                // State as last written by us, so renewal takes a single patch. Cleared when the
+                // outcome of a write is unknown or conflicting, to read the lease before the next.
+                let mut cached = Some(lease_state);
+                loop {
+                    let scheduled = Instant::now() + delay;
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => self.check_stall(scheduled, &tag),
+                        _ = control.resumed.notified() => {}
+                    }
+                    delay = tick;
+                    if control.paused.load(Ordering::SeqCst) {
+                        log::debug!("{}.renewal({}) => paused", self.lease_name, tag);
+                        continue;
+                    }
+                    let started = Instant::now();
+                    let lease_state = match cached.take() {
+                        Some(lease_state) => Ok(lease_state),
+                        None => self.get_state().await,
+                    };
+                    let error = match lease_state {
+                        Ok(lease_state) => {
+                            if lease_state.owner().as_ref() == Some(&holder_id.as_str()) {
+                                // Renew if waiting for another tick would be late.
+                                let extend = Instant::now() + tick > next_renewal;
+                                match self.renew_lease(lease_state, extend).await {
+                                    Ok(renewed) => {
+                                        cached = Some(renewed);
+                                        if extend {
+                                            next_renewal = Instant::now() + renew_interval;
+                                            metrics::record(&self.lease_name, |m| m.renewals += 1);
+                                            ownership.renewed_tx.send_replace(Some(Instant::now()));
+                                            self.record_renewal(started, true);
+                                        }
+                                        failures = 0;
+                                        continue;
+                                    }
+                                    Err(Error::Kube(kube::Error::Api(api_err)))
+                                        if api_err.code == StatusCode::CONFLICT =>
+                                    {
+                                        // Somebody else wrote the lease: find out who, right away.
+                                        log::debug!(
+                                            "renew_lease({}, {}) => conflict",
+                                            self.lease_name,
+                                            tag
+                                        );
+                                        let conflict = kube::Error::Api(api_err).into();
+                                        self.observe_error(Operation::Renew, &conflict);
+                                        delay = Duration::ZERO;
+                                        continue;
+                                    }
+                                    Err(e) => {
+                                        log::error!(
+                                            "renew_lease({}, {}) => {}",
+                                            self.lease_name,
+                                            tag,
+                                            e
+                                        );
+                                        e
+                                    }
+                                }
+                            } else {
+                                match &lease_state.takeover {
+                                    Some(takeover) if takeover.previous_holder == holder_id => {
+                                        log::warn!(
+                                            "{}.renewal({}) => lost ownership; \
+                                             taken over by {:?} at {}; stop renewal",
+                                            self.lease_name,
+                                            tag,
+                                            lease_state.holder(),
+                                            takeover.at
+                                        )
+                                    }
+                                    _ => log::warn!(
+                                        "{}.renewal({}) => lost ownership; \
+                                         new owner: {:?}; stop renewal",
+                                        self.lease_name,
+                                        tag,
+                                        lease_state.owner()
+                                    ),
+                                }
+                                ownership.lose();
+                                metrics::record(&self.lease_name, |m| m.lost += 1);
+                                #[cfg(feature = "otel")]
+                                otel::lost(&self.lease_name, &holder_id, &ownership.correlation_id);
+                                fence.release_all().await;
+                                return;
+                            }
+                        }
+                        Err(e) => {
+                            log::error!("schedule_renewal({}, {}) => {}", self.lease_name, tag, e);
+                            e
+                        }
+                    };
+                    metrics::record(&self.lease_name, |m| m.renewal_errors += 1);
+                    #[cfg(feature = "otel")]
+                    otel::renew_failed(&self.lease_name, &holder_id, &ownership.correlation_id);
+                    self.record_renewal(started, false);
+                    if error.is_unreachable() {
+                        let renewed = ownership.renewed_tx.borrow().unwrap_or(started);
+                        let valid_for = (renewed + self.lease_duration)
+                            .saturating_duration_since(Instant::now());
+                        match self.outage_mode {
+                            OutageMode::Retry => {}
+                            OutageMode::UntilExpiry if !valid_for.is_zero() => {
+                                // Wake up at expiry at the latest, to stop acting on time.
+                                delay = delay.min(valid_for);
+                            }
+                            OutageMode::UntilExpiry => {
+                                log::warn!(
+                                    "{}.renewal({}) => API server unreachable until expiry; \
+                                     stop renewal",
+                                    self.lease_name,
+                                    tag
+                                );
+                                #[cfg(feature = "otel")]
+                                otel::outage_expired(
+                                    &self.lease_name,
+                                    &holder_id,
+                                    &ownership.correlation_id,
+                                );
+                                ownership.lose();
+                                fence.release_all().await;
+                                return;
+                            }
+                            OutageMode::StopImmediately => {
+                                log::warn!(
+                                    "{}.renewal({}) => API server unreachable; stop renewal",
+                                    self.lease_name,
+                                    tag
+                                );
+                                #[cfg(feature = "otel")]
+                                otel::outage_stopped(
+                                    &self.lease_name,
+                                    &holder_id,
+                                    &ownership.correlation_id,
+                                );
+                                ownership.lose();
+                                fence.release_all().await;
+                                return;
+                            }
+                        }
+                    }
+                    if failures == 0 {
+                        failing_since = Instant::now();
+                    }
+                    failures += 1;
+                    match self.retry_decision(Operation::Renew, &error, failures, failing_since) {
+                        None => {}
+                        Some(RetryDecision::Retry(after)) => delay = after,
+                        Some(RetryDecision::Fail) => {
+                            log::warn!(
+                                "{}.renewal({}) => retry policy gave up; stop renewal",
+                                self.lease_name,
+                                tag
+                            );
+                            ownership.lose();
+                            fence.release_all().await;
+                            return;
+                        }
+                    }
+                }
+            },
+            abort_reg,
+        );
+        #[cfg(feature = "tracing")]
+        let renewal = tracing::Instrument::instrument(renewal, span);
+        spawn_named(&task_name, runtime, renewal);
+
+        abort_handle
+    }
+
+    fn record_renewal(&self, started: Instant, ok: bool) {
+        self.renewal_stats.record(started.elapsed(), ok);
+        self.renewal_stats
+            .check(&self.lease_name, self.lease_duration, self.renewal_slack());
+    }
+
+    fn check_stall(&self, scheduled: Instant, holder_id: &str) {
+        if let Some(percent) = self.stall_warning_percent {
+            let delay = Instant::now().saturating_duration_since(scheduled);
+            let threshold = self.lease_duration * percent / 100;
+            if delay > threshold {
+                log::warn!(
+                    "{}.renewal({}) => tick delayed by {:?}, over {}% of lease duration",
+                    &self.lease_name,
+                    holder_id,
+                    delay,
+                    percent
+                );
+            }
+        }
+    }
+
+    /// Renew the lease if `extend`, otherwise only stamp the heartbeat.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                lease = %self.lease_name,
+                holder = ?lease_state.holder,
+                resource_version = %lease_state.resource_version,
+                extend
+            )
+        )
+    )]
+    pub(crate) async fn renew_lease(
+        &self,
+        lease_state: LeaseState,
+        extend: bool,
+    ) -> Result<LeaseState, Error> {
+        let (now, _) = self.now();
+        // Server-side apply drops fields this manager owned but omits, so re-state all of them.
+        let acquire_time: &str = &lease_state
+            .acquire_time
+            .unwrap_or(now)
+            .to_rfc3339_opts(chrono::SecondsFormat::Micros, false);
+        let renew_time = if extend { now } else { lease_state.renew_time };
+        let renewals = lease_state
+            .annotations
+            .get(RENEWALS_ANNOTATION)
+            .and_then(|renewals| renewals.parse::<u64>().ok())
+            .unwrap_or(0);
+        let status = Status {
+            holder: lease_state.holder().unwrap_or_default(),
+            renew_time,
+            renewals: renewals + u64::from(extend),
+        };
+        let renew_time: &str = &renew_time.to_rfc3339_opts(chrono::SecondsFormat::Micros, false);
+        let patch: LeaseObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "coordination.k8s.io/v1",
+            "kind": "Lease",
+            "metadata": {
+                "name": &lease_state.lease_name,
+                "resourceVersion": &lease_state.resource_version,
+                "annotations": self.annotations(now, status, lease_state.takeover.as_ref()),
+            },
+            "spec": {
+                "acquireTime": acquire_time,
+                "renewTime": renew_time,
+                "holderIdentity": &lease_state.holder,
+                "leaseDurationSeconds": self.lease_duration_seconds()?,
+                "leaseTransitions": lease_state.transitions,
+            }
+        }))?;
+
+        let lease_obj = self
+            .api()
+            .patch(
+                &lease_state.lease_name,
+                &PatchParams::apply(&self.field_manager).force(),
+                &kube::api::Patch::Apply(&patch),
+            )
+            .await?;
+        self.observe_server_time(&lease_obj);
+        LeaseState::try_from(lease_obj)
+    }
+
+    pub(crate) async fn current_leader(
+        &self,
+        max_staleness: Duration,
+    ) -> Result<LeaseState, Error> {
+        let cache = self.cache();
+        cache.start(&self.lease_name, || self.watch());
+        if let Some(lease_state) = cache.get(max_staleness) {
+            return Ok(lease_state);
+        }
+        let lease_state = self.get_state().await?;
+        cache.put(lease_state.clone());
+        Ok(lease_state)
+    }
+
+    pub(crate) fn lease_name(&self) -> &str {
+        &self.lease_name
+    }
+
+    pub(crate) async fn get_state(&self) -> Result<LeaseState, Error> {
+        self.api()
+            .get(&self.lease_name)
+            .await
+            .map(LeaseState::try_from)?
+    }
+
+    async fn wait_free(
+        &self,
+        deadline: Option<Instant>,
+        holder: &str,
+        breadcrumbs: &mut Breadcrumbs,
+    ) -> Result<LeaseState, Error> {
+        let mut lease_state = self.poll_state().await?;
+        if lease_state.owner().is_none() {
+            return Ok(lease_state);
+        }
+
+        let mut expo = self.expo.clone();
+        let mut last_renew_time = lease_state.renew_time;
+        loop {
+            if let Some(d) = deadline.filter(|d| Instant::now() >= *d) {
+                return Err(Error::acquire_timeout(d));
+            }
+            let backoff =
+                if self.health_informed_polling && lease_state.renew_time > last_renew_time {
+                    // The holder renews: its lease won't expire before the current term ends.
+                    expo = self.expo.clone();
+                    match deadline {
+                        Some(d) => lease_state
+                            .remaining()
+                            .min(d.saturating_duration_since(Instant::now())),
+                        None => lease_state.remaining(),
+                    }
+                } else {
+                    // Wake up right at expiry if that comes first, rather than after the backoff.
+                    let until_expiry = lease_state.remaining();
+                    match self.next_backoff(&mut expo, deadline) {
+                        Ok(backoff) => backoff.min(until_expiry),
+                        Err(e) => match deadline {
+                            Some(d) if Instant::now() + until_expiry < d => until_expiry,
+                            _ => return Err(e),
+                        },
+                    }
+                };
+            last_renew_time = lease_state.renew_time;
+            breadcrumbs.push(|| Breadcrumb {
+                holder: lease_state.holder.clone(),
+                expires_at: lease_state.expires_at(),
+                backoff,
+            });
+
+            log::debug!(
+                "{}.wait_free({}) => {}:backoff({:?})!",
+                &self.lease_name,
+                holder,
+                lease_state.holder.as_deref().unwrap_or_default(),
+                backoff
+            );
+            tokio::time::sleep(backoff).await;
+
+            lease_state = self.poll_state().await?;
+            if lease_state.owner().is_none() {
+                return Ok(lease_state);
+            }
+        }
+    }
+
+    /// Take the lease over as `holder_id`. On a conflict, re-read the lease and, if it is still
+    /// free (our resourceVersion was merely stale), try again up to `stale_retries` times.
+    /// Return the state written, or the state found if somebody else got the lease.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            skip_all,
+            fields(
+                lease = %self.lease_name,
+                holder = holder_id,
+                resource_version = %lease_state.resource_version
+            )
+        )
+    )]
+    pub(crate) async fn try_overwrite(
+        &self,
+        holder_id: &str,
+        mut lease_state: LeaseState,
+    ) -> Result<LeaseState, Error> {
+        let mut retries = 0;
+        loop {
+            match self.overwrite_once(holder_id, &lease_state).await {
+                Err(Error::Kube(kube::Error::Api(api_err)))
+                    if api_err.code == StatusCode::CONFLICT =>
+                {
+                    log::debug!(
+                        "{}.try_overwrite({}) => conflict",
+                        &self.lease_name,
+                        &holder_id
+                    );
+                    if retries == self.stale_retries {
+                        return Ok(lease_state);
+                    }
+                    lease_state = self.get_state().await?;
+                    if lease_state.owner().is_some() {
+                        return Ok(lease_state);
+                    }
+                    retries += 1;
+                    metrics::record(&self.lease_name, |m| m.stale_retries += 1);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Take the lease over as `holder_id`, once: a stale `lease_state` fails with a conflict.
+    pub(crate) async fn overwrite_once(
+        &self,
+        holder_id: &str,
+        lease_state: &LeaseState,
+    ) -> Result<LeaseState, Error> {
+        let patch = self.overwrite_patch(holder_id, lease_state)?;
+        let lease_obj = self
+            .api()
+            .patch(
+                &self.lease_name,
+                &PatchParams::apply(&self.field_manager).force(),
+                &kube::api::Patch::Apply(&patch),
+            )
+            .await?;
+        self.taken_over(lease_obj, lease_state)
+    }
+
+    /// State of the lease as written by our takeover of `previous`. The write drops the handover
+    /// payload of a released lease, so it is carried over for the new holder.
+    fn taken_over(
+        &self,
+        lease_obj: LeaseObject,
+        previous: &LeaseState,
+    ) -> Result<LeaseState, Error> {
+        self.observe_server_time(&lease_obj);
+        let mut lease_state = LeaseState::try_from(lease_obj)?;
+        if previous.holder.is_none() {
+            lease_state.handover = previous.handover.clone();
+        }
+        Ok(lease_state)
+    }
+
+    /// Apply patch making `holder_id` the holder of the lease last seen as `lease_state`.
+    fn overwrite_patch(
+        &self,
+        holder_id: &str,
+        lease_state: &LeaseState,
+    ) -> Result<LeaseObject, Error> {
+        let (now_time, _) = self.now();
+        let now: &str = &now_time.to_rfc3339_opts(chrono::SecondsFormat::Micros, false);
+        let takeover = lease_state
+            .holder
+            .as_ref()
+            .filter(|previous| previous.as_str() != holder_id)
+            .map(|previous| Takeover {
+                previous_holder: previous.clone(),
+                at: now_time,
+            });
+        let status = Status {
+            holder: holder_id,
+            renew_time: now_time,
+            renewals: 0,
+        };
+        let patch: LeaseObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "coordination.k8s.io/v1",
+            "kind": "Lease",
+            "metadata": {
+                "name": &lease_state.lease_name,
+                "resourceVersion": &lease_state.resource_version,
+                "annotations": self.annotations(now_time, status, takeover.as_ref()),
+            },
+            "spec": {
+                "acquireTime": now,
+                "renewTime": now,
+                "holderIdentity": holder_id,
+                "leaseDurationSeconds": self.lease_duration_seconds()?,
+                // counts every acquisition, so that it can serve as a fencing token
+                "leaseTransitions": lease_state.transitions + 1,
+            }
+        }))?;
+        Ok(patch)
+    }
+}
+
+/// Interval between renewals of a lease of `lease_duration`: `configured` if set, otherwise
+/// leaving [RENEWAL_SLACK] of the lease duration when a renewal is due.
+pub(crate) fn renew_interval(lease_duration: Duration, configured: Option<Duration>) -> Duration {
+    configured
+        .unwrap_or_else(|| lease_duration * (RENEWAL_SLACK.1 - RENEWAL_SLACK.0) / RENEWAL_SLACK.1)
+}
+
+/// Check that a lease of `lease_duration` renewed every `renew_interval` (or by default) can be
+/// kept held.
+pub(crate) fn validate_renewal(
+    lease_duration: Duration,
+    renew_interval: Option<Duration>,
+) -> Result<(), Error> {
+    if lease_duration < MIN_LEASE_DURATION {
+        return Err(Error::Config(format!(
+            "lease duration {:?} is shorter than {:?}",
+            lease_duration, MIN_LEASE_DURATION
+        )));
+    }
+    if let Some(interval) = renew_interval {
+        if interval.is_zero() || interval >= lease_duration {
+            return Err(Error::Config(format!(
+                "renew interval {:?} is not within lease duration {:?}",
+                interval, lease_duration
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) type UtcInstant = chrono::DateTime<chrono::offset::Utc>;
+
+/// Values of the status annotations, see [LeaseLock::with_status_annotations].
+#[derive(Clone, Copy)]
+struct Status<'a> {
+    holder: &'a str,
+    renew_time: UtcInstant,
+    renewals: u64,
+}
+
+/// Record of an expired lease taken over from its previous holder.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Takeover {
+    /// Holder whose expired lease was taken over.
+    pub previous_holder: String,
+    /// Time of the takeover.
+    pub at: UtcInstant,
+}
+
+/// Snapshot of the lease as observed in the cluster.
+#[derive(Clone, Debug)]
+pub struct LeaseState {
+    lease_name: String,
+    holder: Option<String>,
+    renew_time: UtcInstant,
+    acquire_time: Option<UtcInstant>,
+    transitions: i32,
+    heartbeat: Option<UtcInstant>,
+    takeover: Option<Takeover>,
+    annotations: BTreeMap<String, String>,
+    schema_version: u32,
+    lease_duration: chrono::Duration,
+    resource_version: String,
+    /// Payload left by the last holder on release, see [LeaseGuard::handover_payload].
+    handover: Option<String>,
+}
+
+impl TryFrom<LeaseObject> for LeaseState {
+    type Error = crate::lease::Error;
+    fn try_from(lo: LeaseObject) -> Result<Self, Error> {
+        let mut annotations = lo.annotations().clone();
+        let schema_version = schema::upgrade(&mut annotations);
+        let heartbeat = annotations
+            .get(HEARTBEAT_ANNOTATION)
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&chrono::Utc));
+        let takeover = match (
+            annotations.get(PREVIOUS_HOLDER_ANNOTATION),
+            annotations
+                .get(TAKEN_OVER_AT_ANNOTATION)
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok()),
+        ) {
+            (Some(previous_holder), Some(at)) => Some(Takeover {
+                previous_holder: previous_holder.clone(),
+                at: at.with_timezone(&chrono::Utc),
+            }),
+            _ => None,
+        };
+        Ok(LeaseState {
+            lease_name: lo
+                .metadata
+                .name
+                .ok_or_else(|| Error::Format("lease name".into()))?,
+
+            holder: lo.spec.as_ref().and_then(|x| x.holder_identity.clone()),
+
+            renew_time: lo
+                .spec
+                .as_ref()
+                .and_then(|x| x.renew_time.as_ref())
+                .map(|x| x.0)
+                .unwrap_or(chrono::MIN_DATETIME),
+
+            acquire_time: lo
+                .spec
+                .as_ref()
+                .and_then(|x| x.acquire_time.as_ref())
+                .map(|x| x.0),
+
+            transitions: lo
+                .spec
+                .as_ref()
+                .and_then(|x| x.lease_transitions)
+                .unwrap_or(0),
+
+            heartbeat,
+
+            takeover,
+
+            handover: annotations.get(HANDOVER_ANNOTATION).cloned(),
+
+            annotations,
+
+            schema_version,
+
+            lease_duration: chrono::Duration::seconds(
+                (lo.spec.and_then(|x| x.lease_duration_seconds).unwrap_or(0) as u64)
+                    .try_into()
+                    .map_err(Error::from)?,
+            ),
+
+            resource_version: lo
+                .metadata
+                .resource_version
+                .ok_or_else(|| Error::Format("resourceVersion".into()))?,
+        })
+    }
+}
+
+impl LeaseState {
+    /// Name of the lease object.
+    pub fn lease_name(&self) -> &str {
+        &self.lease_name
+    }
+
+    /// Holder identity as written in the lease, even if the lease is expired.
+    pub fn holder(&self) -> Option<&str> {
+        self.holder.as_deref()
+    }
+
+    /// Time the current holder acquired the lease.
+    pub fn acquire_time(&self) -> Option<UtcInstant> {
+        self.acquire_time
+    }
+
+    /// Time the lease was last renewed.
+    pub fn renew_time(&self) -> UtcInstant {
+        self.renew_time
+    }
+
+    /// Lease duration as written in the lease.
+    pub fn lease_duration(&self) -> chrono::Duration {
+        self.lease_duration
+    }
+
+    /// Time the lease expires unless renewed.
+    pub fn expires_at(&self) -> UtcInstant {
+        self.renew_time + self.lease_duration
+    }
+
+    /// Time left until the lease expires unless renewed; zero if already expired.
+    pub fn remaining(&self) -> Duration {
+        (self.expires_at() - chrono::Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Time the current holder has held the lease since acquiring it.
+    pub fn held_for(&self) -> Option<Duration> {
+        let acquire_time = self.acquire_time?;
+        self.holder.as_ref()?;
+        Some(
+            (chrono::Utc::now() - acquire_time)
+                .to_std()
+                .unwrap_or(Duration::ZERO),
+        )
+    }
+
+    /// Time of the holder's last heartbeat, see [LeaseLock::with_heartbeat].
+    pub fn heartbeat(&self) -> Option<UtcInstant> {
+        self.heartbeat
+    }
+
+    /// Takeover that made the current holder acquire the lease, if it was taken over after expiry.
+    pub fn takeover(&self) -> Option<&Takeover> {
+        self.takeover.as_ref()
+    }
+
+    /// Holder that announced its intent to acquire the lease, see [LeaseLock::prepare_acquire].
+    pub fn pending_holder(&self) -> Option<&str> {
+        self.annotations
+            .get(PENDING_HOLDER_ANNOTATION)
+            .map(String::as_str)
+    }
+
+    /// Annotations of the lease object.
+    pub fn annotations(&self) -> &BTreeMap<String, String> {
+        &self.annotations
+    }
+
+    /// Schema version the crate annotations of the lease were written in.
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Number of times the lease changed hands (`leaseTransitions`).
+    pub fn transitions(&self) -> i32 {
+        self.transitions
+    }
+
+    fn expired(&self) -> bool {
+        self.expires_at() <= chrono::Utc::now()
+    }
+
+    pub(crate) fn resource_version(&self) -> &str {
+        &self.resource_version
+    }
+
+    pub(crate) fn owner(&self) -> Option<&str> {
+        if self.expired() {
+            None
+        } else {
+            self.holder.as_deref()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lease::*;
+    use crate::test_util;
+    use futures::stream::StreamExt;
+    use kube::api::DeleteParams;
+    use std::sync::Once;
+    use taken::take;
+    use test_context::{test_context, AsyncTestContext};
+
+    static LOG_INIT: Once = Once::new();
+
+    struct TestContext {
+        pub lease_name: String,
+        pub api: Api,
+        pub lease_lock: LeaseLock,
+    }
+
+    #[async_trait::async_trait]
+    impl AsyncTestContext for TestContext {
+        async fn setup() -> Self {
+            LOG_INIT.call_once(|| env_logger::init());
+
+            let lease_name = test_util::random_lease_name("test-lease");
+            log::debug!("{}.setup()", &lease_name);
+
+            let api = kube::Api::default_namespaced(kube::Client::try_default().await.unwrap());
+            test_util::create_lease(&api, &lease_name).await.unwrap();
+            let lease_lock = LeaseLock::new(api.clone(), lease_name.clone());
+            Self {
+                lease_name,
+                api,
+                lease_lock,
+            }
+        }
+
+        async fn teardown(mut self) {
+            log::debug!("{}.teardown()", &self.lease_name);
+            self.lease_lock.complete_all_operations().await;
+            test_util::delete_lease(&self.api, &self.lease_name)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn raii(ctx: &mut TestContext) {
+        {
+            let _guard = ctx
+                .lease_lock
+                .try_acquire("initial")
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(ctx
+                .lease_lock
+                .try_acquire("within scope")
+                .await
+                .unwrap()
+                .is_none());
+        }
+        ctx.lease_lock
+            .acquire("outside scope", Some(Duration::from_secs(1)))
+            .await
+            .unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn concurrent_locks(ctx: &mut TestContext) {
+        use std::sync::Arc;
+        use tokio::sync::Mutex;
+        let glob = Arc::new(Mutex::new(0));
+        take!(&glob, &ctx);
+        (1..8)
+            .map(|i| async move {
+                let _guard = ctx
+                    .lease_lock
+                    .acquire(&format!("{}", i), Some(Duration::from_secs(20)))
+                    .await
+                    .unwrap();
+                *glob.lock().await = i;
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                assert_eq!(*glob.lock().await, i);
+            })
+            .collect::<futures::stream::FuturesUnordered<_>>()
+            .collect::<Vec<_>>()
+            .await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn verified_concurrent_locks(ctx: &mut TestContext) {
+        use std::sync::Arc;
+        use tokio::sync::Mutex;
+        let glob = Arc::new(Mutex::new(0));
+        let lease_lock =
+            LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone()).with_write_verification(true);
+        {
+            take!(&glob, &lease_lock);
+            (1..8)
+                .map(|i| async move {
+                    let _guard = lease_lock
+                        .acquire(&format!("{}", i), Some(Duration::from_secs(20)))
+                        .await
+                        .unwrap();
+                    *glob.lock().await = i;
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    assert_eq!(*glob.lock().await, i);
+                })
+                .collect::<futures::stream::FuturesUnordered<_>>()
+                .collect::<Vec<_>>()
+                .await;
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn complete(ctx: &mut TestContext) {
+        {
+            let _ = ctx.lease_lock.try_acquire("1").await.unwrap().unwrap();
+        }
+        ctx.lease_lock.complete_all_operations().await;
+        {
+            let _ = ctx.lease_lock.try_acquire("1").await.unwrap().unwrap();
+        }
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn pre_acquire_hook(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_pre_acquire_hook(|_| async { Decision::Veto });
+        assert!(lease_lock
+            .acquire("vetoed", Some(Duration::from_millis(100)))
+            .await
+            .is_err());
+
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_pre_acquire_hook(|lease_state| {
+                let free = lease_state.holder().is_none();
+                async move {
+                    if free {
+                        Decision::Proceed
+                    } else {
+                        Decision::Veto
+                    }
+                }
+            });
+        {
+            let _guard = lease_lock.try_acquire("allowed").await.unwrap().unwrap();
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn warm_up(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_warm_up(Duration::from_millis(100), |_| futures::future::pending());
+        assert!(matches!(
+            lease_lock.try_acquire("never ready").await,
+            Err(Error::WarmUpTimeout)
+        ));
+        lease_lock.complete_all_operations().await;
+
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_warm_up(Duration::from_secs(1), |_| {
+                tokio::time::sleep(Duration::from_millis(10))
+            });
+        {
+            let _guard = lease_lock.try_acquire("ready").await.unwrap().unwrap();
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn hashed_holder(ctx: &mut TestContext) {
+        let lease_lock =
+            LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone()).with_hashed_holder("salt");
+        {
+            let _guard = lease_lock.try_acquire("secret").await.unwrap().unwrap();
+            let holder = ctx
+                .api
+                .get(&ctx.lease_name)
+                .await
+                .unwrap()
+                .spec
+                .unwrap()
+                .holder_identity
+                .unwrap();
+            assert_ne!(holder, "secret");
+            assert!(lease_lock.try_acquire("other").await.unwrap().is_none());
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn watch(ctx: &mut TestContext) {
+        let mut states = ctx.lease_lock.watch().boxed();
+        let _guard = ctx
+            .lease_lock
+            .try_acquire("watched")
+            .await
+            .unwrap()
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while states.next().await.unwrap().holder() != Some("watched") {}
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn durations(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.try_acquire("timed").await.unwrap().unwrap();
+        let lease_state = ctx.lease_lock.client.get_state().await.unwrap();
+        assert!(lease_state.remaining() > Duration::ZERO);
+        assert!(lease_state.remaining() <= Duration::from_secs(10));
+        assert!(lease_state.held_for().unwrap() < Duration::from_secs(10));
+        drop(guard);
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn schema_version(ctx: &mut TestContext) {
+        let guard = ctx
+            .lease_lock
+            .try_acquire("versioned")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(guard.lease_state().schema_version(), SCHEMA_VERSION);
+        assert_eq!(
+            guard.lease_state().annotations()[crate::consts::SCHEMA_VERSION_ANNOTATION],
+            SCHEMA_VERSION.to_string()
+        );
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn short_lease(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(Duration::from_millis(10));
+        assert!(matches!(
+            lease_lock.try_acquire("too short").await,
+            Err(Error::Config(_))
+        ));
+
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(Duration::from_millis(800));
+        {
+            let _guard = lease_lock.try_acquire("short").await.unwrap().unwrap();
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            assert!(lease_lock.try_acquire("other").await.unwrap().is_none());
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn renew_interval(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration_sec(2)
+            .with_renew_interval(Duration::from_secs(2));
+        assert!(matches!(
+            lease_lock.try_acquire("too long").await,
+            Err(Error::Config(_))
+        ));
+
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration_sec(2)
+            .with_renew_interval(Duration::from_millis(1500));
+        {
+            let guard = lease_lock.try_acquire("late").await.unwrap().unwrap();
+            let renew_time = guard.lease_state().renew_time();
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let lease_state = lease_lock.current_leader(Duration::ZERO).await.unwrap();
+            assert_eq!(lease_state.renew_time(), renew_time);
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            assert!(lease_lock.try_acquire("other").await.unwrap().is_none());
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn release_debounce(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration_sec(5)
+            .with_release_debounce(Duration::from_millis(500));
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        let acquire_time = guard.lease_state().acquire_time();
+        drop(guard);
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        assert_eq!(guard.lease_state().acquire_time(), acquire_time);
+        drop(guard);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let lease_state = lease_lock.current_leader(Duration::ZERO).await.unwrap();
+        assert_eq!(lease_state.owner(), None);
+        lease_lock.complete_all_operations().await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn long_lease(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration_sec(3 * 3600)
+            .with_heartbeat(Duration::from_millis(500));
+        {
+            let guard = lease_lock.try_acquire("long").await.unwrap().unwrap();
+            tokio::time::sleep(Duration::from_secs(2)).await;
+            let lease_state = lease_lock.client.get_state().await.unwrap();
+            assert_eq!(lease_state.renew_time, guard.lease_state.renew_time);
+            assert!(lease_state.heartbeat().unwrap() > guard.lease_state.renew_time);
+            assert!(lease_lock
+                .reap_orphaned(Duration::from_secs(60))
+                .await
+                .unwrap()
+                .is_none());
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    async fn write_orphan(ctx: &TestContext, lease_duration_sec: i32) {
+        let now = chrono::Utc::now();
+        let heartbeat = now - chrono::Duration::hours(1);
+        let patch: LeaseObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "coordination.k8s.io/v1",
+            "kind": "Lease",
+            "metadata": {
+                "name": &ctx.lease_name,
+                "annotations": {
+                    HEARTBEAT_ANNOTATION:
+                        heartbeat.to_rfc3339_opts(chrono::SecondsFormat::Micros, false),
+                },
+            },
+            "spec": {
+                "acquireTime": now.to_rfc3339_opts(chrono::SecondsFormat::Micros, false),
+                "renewTime": now.to_rfc3339_opts(chrono::SecondsFormat::Micros, false),
+                "holderIdentity": "crashed",
+                "leaseDurationSeconds": lease_duration_sec,
+            }
+        }))
+        .unwrap();
+        ctx.api
+            .patch(
+                &ctx.lease_name,
+                &PatchParams::apply(crate::consts::DEFAULT_FIELD_MANAGER).force(),
+                &kube::api::Patch::Apply(&patch),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn renewal_after_foreign_write(ctx: &mut TestContext) {
+        let lease_lock =
+            LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone()).with_lease_duration_sec(2);
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        // Bump the resourceVersion, so the cached state of renewal turns stale.
+        let label = serde_json::json!({ "metadata": { "labels": { "foreign": "write" } } });
+        ctx.api
+            .patch(
+                &ctx.lease_name,
+                &PatchParams::default(),
+                &kube::api::Patch::Merge(&label),
+            )
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert!(guard.is_held());
+        let lease_state = lease_lock.current_leader(Duration::ZERO).await.unwrap();
+        assert_eq!(lease_state.owner(), Some("a"));
+        assert!(lease_state.renew_time() > guard.lease_state().renew_time());
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn bulk_watch(ctx: &mut TestContext) {
+        let label =
+            serde_json::json!({ "metadata": { "labels": { "bulk-test": &ctx.lease_name } } });
+        ctx.api
+            .patch(
+                &ctx.lease_name,
+                &PatchParams::default(),
+                &kube::api::Patch::Merge(&label),
+            )
+            .await
+            .unwrap();
+        let selector = format!("bulk-test={}", &ctx.lease_name);
+        let bulk_watch = BulkWatch::new(ctx.api.clone(), &selector, defaults::current().expo);
+        let lease_lock =
+            LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone()).with_bulk_watch(&bulk_watch);
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        let leader = lease_lock
+            .current_leader(Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(leader.holder(), Some("a"));
+        let waiting = lease_lock.acquire("b", Some(Duration::from_secs(5)));
+        tokio::pin!(waiting);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(300), &mut waiting)
+                .await
+                .is_err()
+        );
+        drop(guard);
+        let guard = waiting.await.unwrap();
+        assert_eq!(guard.lease_state().holder(), Some("b"));
+    }
+
+    #[test]
+    fn error_details() {
+        let error = Error::from(kube::Error::Api(kube::error::ErrorResponse {
+            status: "Failure".into(),
+            message: "too many requests".into(),
+            reason: "TooManyRequests".into(),
+            code: 429,
+        }));
+        assert_eq!(error.status_code(), Some(StatusCode::TOO_MANY_REQUESTS));
+        assert_eq!(error.reason(), Some("TooManyRequests"));
+        assert_eq!(Error::Config("x".into()).status_code(), None);
+    }
+
+    #[test]
+    fn unreachable() {
+        let api_error = |code| {
+            Error::from(kube::Error::Api(kube::error::ErrorResponse {
+                status: "Failure".into(),
+                message: String::new(),
+                reason: String::new(),
+                code,
+            }))
+        };
+        assert!(api_error(503).is_unreachable());
+        assert!(!api_error(409).is_unreachable());
+        assert!(!Error::Config("x".into()).is_unreachable());
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn two_phase_acquire(ctx: &mut TestContext) {
+        let pending = ctx.lease_lock.prepare_acquire("a").await.unwrap();
+        let lease_state = ctx.lease_lock.client().get_state().await.unwrap();
+        assert_eq!(lease_state.pending_holder(), Some("a"));
+        assert_eq!(lease_state.holder(), None);
+        let guard = pending.commit(None).await.unwrap();
+        let lease_state = ctx.lease_lock.client().get_state().await.unwrap();
+        assert_eq!(lease_state.pending_holder(), None);
+        assert_eq!(lease_state.holder(), Some("a"));
+        drop(guard);
+
+        let pending = ctx.lease_lock.prepare_acquire("b").await.unwrap();
+        pending.cancel().await.unwrap();
+        let lease_state = ctx.lease_lock.client().get_state().await.unwrap();
+        assert_eq!(lease_state.pending_holder(), None);
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn update_guarded(ctx: &mut TestContext) {
+        let target = format!("{}-target", &ctx.lease_name);
+        test_util::create_lease(&ctx.api, &target).await.unwrap();
+        let updated = ctx
+            .lease_lock
+            .update_guarded("a", None, &ctx.api, &target, |obj: &mut LeaseObject| {
+                obj.labels_mut().insert("updated".into(), "yes".into());
+            })
+            .await
+            .unwrap();
+        let token = updated.annotations()[crate::consts::FENCING_TOKEN_ANNOTATION].clone();
+        assert_eq!(updated.labels()["updated"], "yes");
+
+        let newer = serde_json::json!({
+            "metadata": { "annotations": { crate::consts::FENCING_TOKEN_ANNOTATION: "1000" } },
+        });
+        ctx.api
+            .patch(
+                &target,
+                &PatchParams::default(),
+                &kube::api::Patch::Merge(&newer),
+            )
+            .await
+            .unwrap();
+        let fenced = ctx
+            .lease_lock
+            .update_guarded("a", None, &ctx.api, &target, |_: &mut LeaseObject| {})
+            .await;
+        match fenced {
+            Err(Error::Fenced { token: ours, seen }) => {
+                assert_eq!(ours, token.parse::<i32>().unwrap() + 1);
+                assert_eq!(seen, 1000);
+            }
+            _ => panic!("expected fenced"),
+        }
+        test_util::delete_lease(&ctx.api, &target).await.unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn stale_retries(ctx: &mut TestContext) {
+        let client = ctx.lease_lock.client();
+        let stale = client.get_state().await.unwrap();
+        client.annotate("unrelated", Some("change")).await.unwrap();
+        let lease_state = client.try_overwrite("a", stale.clone()).await.unwrap();
+        assert_eq!(lease_state.holder(), Some("a"));
+
+        let lease_lock =
+            LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone()).with_stale_retries(0);
+        let lease_state = lease_lock.client().try_overwrite("b", stale).await.unwrap();
+        assert_eq!(lease_state.holder(), None);
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn handover_payload(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        assert_eq!(guard.handover_payload(), None);
+        guard.release_with_handover("offset=42").await.unwrap();
+        let guard = ctx.lease_lock.acquire("b", None).await.unwrap();
+        assert_eq!(guard.handover_payload(), Some("offset=42"));
+        guard.release().await.unwrap();
+        let guard = ctx.lease_lock.acquire("c", None).await.unwrap();
+        assert_eq!(guard.handover_payload(), None);
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn release_all(ctx: &mut TestContext) {
+        let names: Vec<_> = (0..3)
+            .map(|i| format!("{}-batch-{}", &ctx.lease_name, i))
+            .collect();
+        let mut guards = Vec::new();
+        for name in &names {
+            let lease_lock =
+                LeaseLock::new(ctx.api.clone(), name.clone()).with_create_if_missing(true);
+            guards.push(lease_lock.acquire("a", None).await.unwrap());
+        }
+        let released = crate::release_all(guards, 2).await;
+        for (name, released) in names.iter().zip(released) {
+            let released = released.unwrap();
+            assert_eq!(released.lease_name(), name.as_str());
+            assert_eq!(released.holder(), None);
+            test_util::delete_lease(&ctx.api, name).await.unwrap();
+        }
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn drain(ctx: &mut TestContext) {
+        let dependency = LeaseLock::new(ctx.api.clone(), format!("{}-dep", &ctx.lease_name))
+            .with_create_if_missing(true);
+        let dependency_guard = dependency.acquire("a", None).await.unwrap();
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        let released = ctx.lease_lock.drain(vec![guard, dependency_guard]).await;
+        assert!(released.iter().all(|released| released.is_ok()));
+        test_util::assert_held_by(&ctx.api, &ctx.lease_name, None).await;
+        assert!(matches!(
+            ctx.lease_lock.try_acquire("a").await,
+            Err(Error::Drained)
+        ));
+        test_util::delete_lease(&ctx.api, dependency.name())
+            .await
+            .unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn check_round_trips(ctx: &mut TestContext) {
+        let rtt = ctx.lease_lock.check_round_trips().await.unwrap();
+        assert!(rtt > Duration::ZERO);
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(MIN_LEASE_DURATION);
+        match lease_lock.check_round_trips().await {
+            Ok(rtt) => assert!(rtt * SAFE_RTT_MULTIPLE <= Duration::from_millis(60)),
+            Err(Error::UnsafeLeaseDuration { margin, rtt }) => {
+                assert!(margin < rtt * SAFE_RTT_MULTIPLE)
+            }
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn release_spares_new_holder(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        write_orphan(ctx, 10).await;
+        drop(guard);
+        ctx.lease_lock.complete_all_operations().await;
+        let lease_state = ctx.lease_lock.client().get_state().await.unwrap();
+        assert_eq!(lease_state.holder(), Some("crashed"));
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn leadership_or(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        let shutdown = tokio::time::sleep(Duration::from_millis(200));
+        let raced = ctx.lease_lock.leadership_or("b", shutdown).await.unwrap();
+        assert!(matches!(raced, futures::future::Either::Right(())));
+        drop(guard);
+        ctx.lease_lock.complete_all_operations().await;
+        let raced = ctx
+            .lease_lock
+            .leadership_or("b", futures::future::pending::<()>())
+            .await
+            .unwrap();
+        assert!(matches!(raced, futures::future::Either::Left(_)));
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn create_on_acquire(ctx: &mut TestContext) {
+        test_util::delete_lease(&ctx.api, &ctx.lease_name)
+            .await
+            .unwrap();
+        let lease_lock =
+            LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone()).with_create_if_missing(true);
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        assert_eq!(guard.lease_state().holder(), Some("a"));
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn lease_template(ctx: &mut TestContext) {
+        test_util::delete_lease(&ctx.api, &ctx.lease_name)
+            .await
+            .unwrap();
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_template(|lease| {
+                lease
+                    .labels_mut()
+                    .insert("team".to_string(), "storage".to_string());
+            });
+        lease_lock
+            .client()
+            .create_if_missing(&Default::default())
+            .await
+            .unwrap();
+        let lease_obj = ctx.api.get(&ctx.lease_name).await.unwrap();
+        assert_eq!(lease_obj.labels().get("team").unwrap(), "storage");
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn zone_spread(ctx: &mut TestContext) {
+        let zoned = |zone: &str| {
+            LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+                .with_zone(zone)
+                .with_zone_spread(Duration::from_secs(1))
+        };
+        let lock_a = zoned("zone-a");
+        drop(lock_a.acquire("a1", None).await.unwrap());
+        lock_a.complete_all_operations().await;
+
+        let lock_b = zoned("zone-b");
+        let same_zone = lock_a.acquire("a2", Some(Duration::from_secs(2)));
+        let other_zone = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            lock_b.acquire("b1", None).await.unwrap()
+        };
+        let (same_zone, other_zone) = futures::join!(same_zone, other_zone);
+        assert_eq!(other_zone.lease_state().holder(), Some("b1"));
+        assert_eq!(
+            other_zone
+                .lease_state()
+                .annotations()
+                .get(HOLDER_ZONE_ANNOTATION)
+                .unwrap(),
+            "zone-b"
+        );
+        assert!(matches!(same_zone, Err(Error::AcquireTimeout { .. })));
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn foreign_metadata(ctx: &mut TestContext) {
+        let patch: LeaseObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "coordination.k8s.io/v1",
+            "kind": "Lease",
+            "metadata": {
+                "name": &ctx.lease_name,
+                "labels": { "gitops/app": "demo" },
+                "annotations": { "gitops/revision": "1" },
+            },
+        }))
+        .unwrap();
+        ctx.api
+            .patch(
+                &ctx.lease_name,
+                &PatchParams::apply("gitops"),
+                &kube::api::Patch::Apply(&patch),
+            )
+            .await
+            .unwrap();
+        for spec_only in [false, true] {
+            let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+                .with_lease_duration(Duration::from_secs(1))
+                .with_spec_only_apply(spec_only);
+            let guard = lease_lock.acquire("a", None).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(1200)).await;
+            drop(guard);
+            lease_lock.complete_all_operations().await;
+            let lease_obj = ctx.api.get(&ctx.lease_name).await.unwrap();
+            assert_eq!(lease_obj.labels().get("gitops/app").unwrap(), "demo");
+            assert_eq!(lease_obj.annotations().get("gitops/revision").unwrap(), "1");
+            if spec_only {
+                assert_eq!(lease_obj.annotations().len(), 1);
+            }
+        }
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn wake_at_expiry(ctx: &mut TestContext) {
+        write_orphan(ctx, 1).await;
+        // The backoff reaches 1s before the orphan expires; the takeover must not wait it out.
+        let guard = ctx
+            .lease_lock
+            .acquire("b", Some(Duration::from_millis(1200)))
+            .await
+            .unwrap();
+        assert_eq!(guard.lease_state().holder(), Some("b"));
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn incumbent_grace(ctx: &mut TestContext) {
+        write_orphan(ctx, 1).await;
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_incumbent_grace(Duration::from_secs(1));
+        let challenger = lease_lock.acquire("b", Some(Duration::from_secs(3)));
+        let incumbent = async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            ctx.lease_lock.acquire("crashed", None).await.unwrap()
+        };
+        let (challenger, incumbent) = futures::join!(challenger, incumbent);
+        assert_eq!(incumbent.lease_state().holder(), Some("crashed"));
+        assert!(matches!(challenger, Err(Error::AcquireTimeout { .. })));
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn reattach_and_reap(ctx: &mut TestContext) {
+        write_orphan(ctx, 3600).await;
+        assert!(ctx.lease_lock.reattach("other").await.unwrap().is_none());
+        {
+            let _guard = ctx.lease_lock.reattach("crashed").await.unwrap().unwrap();
+        }
+        ctx.lease_lock.complete_all_operations().await;
+
+        write_orphan(ctx, 3600).await;
+        assert!(ctx
+            .lease_lock
+            .try_acquire("janitor")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(ctx
+            .lease_lock
+            .reap_orphaned(Duration::from_secs(60))
+            .await
+            .unwrap()
+            .is_some());
+        assert!(ctx
+            .lease_lock
+            .try_acquire("janitor")
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn guard_token(ctx: &mut TestContext) {
+        // the worker dies holding the lease
+        let worker = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone());
+        let guard = worker.acquire("worker", None).await.unwrap();
+        let token: GuardToken = guard.to_token().to_string().parse().unwrap();
+        std::mem::forget(guard);
+
+        let adopted = ctx.lease_lock.from_token(&token).await.unwrap().unwrap();
+        assert_eq!(adopted.lease_state().holder(), Some("worker"));
+        drop(adopted);
+        ctx.lease_lock.complete_all_operations().await;
+        let _guard = ctx.lease_lock.acquire("other", None).await.unwrap();
+        assert!(ctx.lease_lock.from_token(&token).await.unwrap().is_none());
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn takeover(ctx: &mut TestContext) {
+        write_orphan(ctx, 1).await;
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone());
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        {
+            let _guard = lease_lock.try_acquire("successor").await.unwrap().unwrap();
+            let lease_state = lease_lock.superseded("crashed").await.unwrap().unwrap();
+            assert_eq!(lease_state.holder(), Some("successor"));
+            assert_eq!(lease_state.takeover().unwrap().previous_holder, "crashed");
+            assert!(lease_lock.superseded("successor").await.unwrap().is_none());
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn lost_ownership(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(Duration::from_secs(1));
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        let scoped = Arc::new(());
+        guard.spawn_scoped({
+            let scoped = scoped.clone();
+            async move {
+                let _scoped = scoped;
+                futures::future::pending::<()>().await
+            }
+        });
+        assert!(guard.is_held());
+        assert!(crate::require_leadership(&guard).is_ok());
+        write_orphan(ctx, 10).await;
+        tokio::time::timeout(Duration::from_secs(2), guard.lost())
+            .await
+            .unwrap();
+        assert!(!guard.is_held());
+        let not_leader = crate::require_leadership(&guard).unwrap_err();
+        assert_eq!(not_leader.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(Arc::strong_count(&scoped), 1);
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn require_leadership_failing_renewals(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(Duration::from_secs(1));
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        assert!(crate::require_leadership(&guard).is_ok());
+        let unreachable = kube::Config::new("http://127.0.0.1:1".parse().unwrap());
+        let client = kube::Client::try_from(unreachable).unwrap();
+        lease_lock.rebind(kube::Api::namespaced(client, "default"));
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        // renewals keep failing, but the guard is still held with the default outage mode
+        assert!(guard.is_held());
+        assert!(crate::require_leadership(&guard).is_err());
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn strict_takeover(ctx: &mut TestContext) {
+        write_orphan(ctx, 1).await;
+        let lease_lock =
+            LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone()).with_strict_takeover(true);
+        assert!(lease_lock.try_acquire("a").await.unwrap().is_none());
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        {
+            let guard = lease_lock.try_acquire("a").await.unwrap().unwrap();
+            assert_eq!(
+                guard.lease_state().takeover().unwrap().previous_holder,
+                "crashed"
+            );
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    struct CountingResource {
+        held: std::sync::atomic::AtomicBool,
+        fail: bool,
+    }
+
+    impl FencedResource for CountingResource {
+        fn acquire<'a>(
+            &'a self,
+            _lease_state: &'a LeaseState,
+        ) -> BoxFuture<'a, Result<(), BoxError>> {
+            async move {
+                if self.fail {
+                    return Err("unavailable".into());
+                }
+                self.held.store(true, std::sync::atomic::Ordering::SeqCst);
+                Ok(())
+            }
+            .boxed()
+        }
+
+        fn release(&self) -> BoxFuture<'_, ()> {
+            async move { self.held.store(false, std::sync::atomic::Ordering::SeqCst) }.boxed()
+        }
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn fenced_resource(ctx: &mut TestContext) {
+        let resource = Arc::new(CountingResource {
+            held: Default::default(),
+            fail: false,
+        });
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_fenced_resource(resource.clone());
+        {
+            let _guard = lease_lock.try_acquire("fenced").await.unwrap().unwrap();
+            assert!(resource.held.load(std::sync::atomic::Ordering::SeqCst));
+        }
+        lease_lock.complete_all_operations().await;
+        assert!(!resource.held.load(std::sync::atomic::Ordering::SeqCst));
+
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_fenced_resource(resource.clone())
+            .with_fenced_resource(Arc::new(CountingResource {
+                held: Default::default(),
+                fail: true,
+            }));
+        assert!(matches!(
+            lease_lock.try_acquire("fenced").await,
+            Err(Error::FencedResource(_))
+        ));
+        lease_lock.complete_all_operations().await;
+        assert!(!resource.held.load(std::sync::atomic::Ordering::SeqCst));
+        assert!(ctx.lease_lock.try_acquire("next").await.unwrap().is_some());
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn shared_lease(ctx: &mut TestContext) {
+        use crate::shared::SharedLeaseLock;
+        let shared = SharedLeaseLock::new(ctx.api.clone(), ctx.lease_name.clone(), 2);
+        {
+            let a = shared.try_acquire("a").await.unwrap().unwrap();
+            let _b = shared.try_acquire("b").await.unwrap().unwrap();
+            assert!(shared.try_acquire("c").await.unwrap().is_none());
+            assert_eq!(shared.holders().await.unwrap(), vec!["a", "b"]);
+            drop(a);
+            let _c = shared
+                .acquire("c", Some(Duration::from_secs(5)))
+                .await
+                .unwrap();
+        }
+        shared.complete_all_operations().await;
+        assert!(shared.holders().await.unwrap().is_empty());
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn shared_lease_lost(ctx: &mut TestContext) {
+        use crate::shared::SharedLeaseLock;
+        let shared = SharedLeaseLock::new(ctx.api.clone(), ctx.lease_name.clone(), 1)
+            .with_lease_duration(Duration::from_secs(1));
+        let guard = shared.try_acquire("a").await.unwrap().unwrap();
+        assert!(guard.is_held());
+        let evict = serde_json::json!({
+            "metadata": { "annotations": { crate::consts::CO_HOLDERS_ANNOTATION: null } },
+        });
+        ctx.api
+            .patch(
+                &ctx.lease_name,
+                &PatchParams::default(),
+                &kube::api::Patch::Merge(&evict),
+            )
+            .await
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(2), guard.lost())
+            .await
+            .unwrap();
+        assert!(!guard.is_held());
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn orchestrated_restart(ctx: &mut TestContext) {
+        use crate::ops::{announce_ready, orchestrate_restart, Handover};
+        let guard = ctx.lease_lock.try_acquire("leader").await.unwrap().unwrap();
+        let successor = tokio::spawn({
+            let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone());
+            async move {
+                announce_ready(&lease_lock, "successor", Duration::from_secs(10))
+                    .await
+                    .unwrap();
+                {
+                    let _guard = lease_lock
+                        .acquire("successor", Some(Duration::from_secs(10)))
+                        .await
+                        .unwrap();
+                }
+                lease_lock.complete_all_operations().await;
+            }
+        });
+        match orchestrate_restart(guard, Duration::from_secs(10)).await {
+            Handover::Done { successor } => assert_eq!(successor, "successor"),
+            Handover::Kept { reason, .. } => panic!("kept leadership: {}", reason),
+        }
+        successor.await.unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn one_shot(ctx: &mut TestContext) {
+        let config = kube::Config::infer().await.unwrap();
+        let namespace = config.default_namespace.clone();
+        let client = kube::Client::try_from(config).unwrap();
+        let lease_name = format!("{}-oneshot", &ctx.lease_name);
+        let output = crate::lock(
+            client,
+            &namespace,
+            &lease_name,
+            "job",
+            Some(Duration::from_secs(5)),
+            async { 42 },
+        )
+        .await
+        .unwrap();
+        assert_eq!(output, 42);
+        ctx.api
+            .delete(&lease_name, &DeleteParams::default())
+            .await
+            .unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn lease_client(ctx: &mut TestContext) {
+        let client = ctx.lease_lock.lease_client();
+        let held = client.try_acquire_once("a").await.unwrap().unwrap();
+        assert_eq!(held.holder(), Some("a"));
+        assert!(client.try_acquire_once("b").await.unwrap().is_none());
+        let renewed = client.renew(held.clone()).await.unwrap();
+        assert!(renewed.renew_time() >= held.renew_time());
+        assert!(client.release(&held).await.is_err());
+        let released = client.release(&renewed).await.unwrap();
+        assert_eq!(released.holder(), None);
+        // try_acquire_once writes through take_over, which fails on a stale state rather than retry
+        match client.take_over("b", &renewed).await {
+            Err(Error::Kube(kube::Error::Api(api_err))) => {
+                assert_eq!(api_err.code, StatusCode::CONFLICT)
+            }
+            _ => panic!("expected conflict"),
+        }
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn rebind(ctx: &mut TestContext) {
+        assert_eq!(ctx.lease_lock.name(), ctx.lease_name);
+        assert!(ctx.lease_lock.namespace().is_some());
+        let client = kube::Client::try_default().await.unwrap();
+        let clone = ctx.lease_lock.clone();
+        clone.rebind(kube::Api::namespaced(client.clone(), "kube-public"));
+        assert_eq!(ctx.lease_lock.namespace().as_deref(), Some("kube-public"));
+        ctx.lease_lock.rebind(kube::Api::default_namespaced(client));
+        let _guard = clone.acquire("a", None).await.unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn current_leader(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        let lease_state = ctx
+            .lease_lock
+            .current_leader(Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(lease_state.holder(), Some("a"));
+        drop(guard);
+        ctx.lease_lock.complete_all_operations().await;
+        let lease_state = ctx.lease_lock.current_leader(Duration::ZERO).await.unwrap();
+        assert_eq!(lease_state.holder(), None);
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn state_file(ctx: &mut TestContext) {
+        let path = std::env::temp_dir().join(format!("{}.json", &ctx.lease_name));
+        let new_lock = || {
+            LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+                .with_state_file(&path, Duration::from_millis(500))
+        };
+        let crashed = new_lock().acquire("a", None).await.unwrap();
+        let acquire_time = crashed.lease_state().acquire_time();
+        std::mem::forget(crashed);
+
+        let lease_lock = new_lock();
+        let guard = lease_lock.resume("a", None).await.unwrap();
+        assert_eq!(guard.lease_state().acquire_time(), acquire_time);
+        drop(guard);
+        lease_lock.complete_all_operations().await;
+
+        let started = Instant::now();
+        let guard = lease_lock.resume("a", None).await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(500));
+        drop(guard);
+        lease_lock.complete_all_operations().await;
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn priority(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("holder", None).await.unwrap();
+        let low = ctx
+            .lease_lock
+            .acquire_with_priority("low", None, Priority::Low);
+        let high = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            ctx.lease_lock
+                .acquire_with_priority("high", None, Priority::High)
+                .await
+        };
+        let release = async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            drop(guard);
+        };
+        let (_, first) = tokio::join!(release, async {
+            tokio::select! {
+                low = low => low.unwrap(),
+                high = high => high.unwrap(),
+            }
+        });
+        assert_eq!(first.lease_state().holder(), Some("high"));
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn audit_annotations(ctx: &mut TestContext) {
+        let ticket = ("example.com/ticket".to_string(), "OPS-1".to_string());
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_audit_annotations(BTreeMap::from([ticket.clone()]));
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        assert_eq!(
+            guard.lease_state().annotations().get(&ticket.0),
+            Some(&ticket.1)
+        );
+        drop(guard);
+        lease_lock.complete_all_operations().await;
+        let lease_state = lease_lock.client.get_state().await.unwrap();
+        assert!(!lease_state.annotations().contains_key(&ticket.0));
+    }
+
+    #[cfg(feature = "sidecar")]
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn sidecar(ctx: &mut TestContext) {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+        let path = std::env::temp_dir().join(format!("{}.sock", &ctx.lease_name));
+        tokio::spawn(crate::sidecar::serve(ctx.api.clone(), path.clone()));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let stream = tokio::net::UnixStream::connect(&path).await.unwrap();
+        let (read, mut write) = stream.into_split();
+        let mut lines = BufReader::new(read).lines();
+        let request = format!("ACQUIRE {0} a\nHOLDER {0}\n", &ctx.lease_name);
+        write.write_all(request.as_bytes()).await.unwrap();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "OK");
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "OK a");
+        drop(write);
+        drop(lines);
+        ctx.lease_lock
+            .wait_until_free(Some(Duration::from_secs(2)))
+            .await
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn node_lock(ctx: &mut TestContext) {
+        std::env::set_var("NODE_NAME", "test-node");
+        assert!(matches!(
+            crate::NodeLock::for_node(ctx.api.clone(), &ctx.lease_name, "other-node"),
+            Err(Error::Config(_))
+        ));
+        let node_lock = crate::NodeLock::new(ctx.api.clone(), &ctx.lease_name).unwrap();
+        let guard = node_lock.acquire("a", None).await.unwrap();
+        assert_eq!(
+            guard.lease_state().lease_name(),
+            format!("{}-test-node", &ctx.lease_name)
+        );
+        drop(guard);
+        node_lock.complete_all_operations().await;
+        test_util::delete_lease(&ctx.api, &format!("{}-test-node", &ctx.lease_name))
+            .await
+            .unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn shared_across_tasks(ctx: &mut TestContext) {
+        let lease_lock = Arc::new(LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone()));
+        let task = tokio::spawn({
+            let lease_lock = lease_lock.clone();
+            async move {
+                let guard = lease_lock.acquire("a", None).await.unwrap();
+                drop(guard);
+            }
+        });
+        task.await.unwrap();
+        let cloned = lease_lock.as_ref().clone();
+        cloned.complete_all_operations().await;
+        test_util::assert_held_by(&ctx.api, &ctx.lease_name, None).await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn reaper(ctx: &mut TestContext) {
+        write_orphan(ctx, 1).await;
+        // Keep the reaper off the leases of concurrent tests.
+        let label =
+            serde_json::json!({ "metadata": { "labels": { "reaper-test": &ctx.lease_name } } });
+        ctx.api
+            .patch(
+                &ctx.lease_name,
+                &PatchParams::default(),
+                &kube::api::Patch::Merge(&label),
+            )
+            .await
+            .unwrap();
+        let client = kube::Client::try_default().await.unwrap();
+        let namespace = ctx.lease_lock.namespace().unwrap();
+        let reaper = crate::Reaper::new(client.clone(), &namespace)
+            .with_grace(Duration::from_secs(60))
+            .with_label_selector(format!("reaper-test={}", &ctx.lease_name));
+        let reaped = |reaped: Vec<LeaseState>| {
+            reaped
+                .iter()
+                .any(|lease_state| lease_state.lease_name() == ctx.lease_name)
+        };
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(!reaped(reaper.reap_once().await.unwrap()));
+        let lease_state = ctx.lease_lock.current_leader(Duration::ZERO).await.unwrap();
+        assert_eq!(lease_state.holder(), Some("crashed"));
+
+        let reaper = reaper.with_grace(Duration::ZERO);
+        assert!(reaped(reaper.reap_once().await.unwrap()));
+        let lease_state = ctx.lease_lock.current_leader(Duration::ZERO).await.unwrap();
+        assert_eq!(lease_state.holder(), None);
+        let events: kube::Api<k8s_openapi::api::core::v1::Event> =
+            kube::Api::namespaced(client, &namespace);
+        let list_params = kube::api::ListParams::default()
+            .fields(&format!("involvedObject.name={}", &ctx.lease_name));
+        let events = events.list(&list_params).await.unwrap();
+        assert!(events
+            .iter()
+            .any(|event| event.reason.as_deref() == Some("ExpiredHolderReaped")));
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn tiered_lock(ctx: &mut TestContext) {
+        let group_lease = format!("{}-zone-a", &ctx.lease_name);
+        let a = crate::TieredLock::new(ctx.api.clone(), &ctx.lease_name, "zone-a");
+        let b = crate::TieredLock::new(ctx.api.clone(), &ctx.lease_name, "zone-a");
+        let c = crate::TieredLock::new(ctx.api.clone(), &ctx.lease_name, "zone-b");
+        let guard = a.acquire("a", None).await.unwrap();
+        assert_eq!(guard.lease_state().lease_name(), ctx.lease_name.as_str());
+        assert_eq!(guard.local().lease_state().lease_name(), group_lease);
+
+        // b is stopped within its group, c contends for the global lease.
+        let (b_acquired, c_acquired) = tokio::join!(
+            b.acquire("b", Some(Duration::from_secs(2))),
+            c.acquire("c", Some(Duration::from_secs(2)))
+        );
+        assert!(matches!(b_acquired, Err(Error::AcquireTimeout { .. })));
+        assert!(matches!(c_acquired, Err(Error::AcquireTimeout { .. })));
+        test_util::assert_held_by(&ctx.api, &group_lease, Some("a")).await;
+        test_util::assert_held_by(&ctx.api, &format!("{}-zone-b", &ctx.lease_name), Some("c"))
+            .await;
+        drop(guard);
+
+        let guard = b.acquire("b", Some(Duration::from_secs(5))).await.unwrap();
+        assert!(guard.is_held());
+        drop(guard);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        for group in ["zone-a", "zone-b"] {
+            test_util::delete_lease(&ctx.api, &format!("{}-{}", &ctx.lease_name, group))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn cluster_lock(ctx: &mut TestContext) {
+        let client = kube::Client::try_default().await.unwrap();
+        let namespace = ctx.lease_lock.namespace().unwrap();
+        let cluster_lock = crate::ClusterLock::in_namespace(client, &namespace, &ctx.lease_name);
+        let guard = cluster_lock.acquire("a", None).await.unwrap();
+        let lease_name = format!("cluster-{}", &ctx.lease_name);
+        assert_eq!(guard.lease_state().lease_name(), lease_name);
+        drop(guard);
+        cluster_lock.complete_all_operations().await;
+        test_util::delete_lease(&ctx.api, &lease_name)
+            .await
+            .unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn acquire_shared(ctx: &mut TestContext) {
+        let (first, second) = tokio::join!(
+            ctx.lease_lock.acquire_shared("a", None),
+            ctx.lease_lock.acquire_shared("a", None)
+        );
+        let (first, second) = (first.unwrap(), second.unwrap());
+        assert!(Arc::ptr_eq(&first, &second));
+        drop(first);
+        assert!(second.is_held());
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn expiry_stream(ctx: &mut TestContext) {
+        let mut crossings = crate::expiry_stream(ctx.api.clone(), &ctx.lease_name).boxed();
+        write_orphan(ctx, 1).await;
+        let crossing = tokio::time::timeout(Duration::from_secs(3), crossings.next())
+            .await
+            .unwrap()
+            .unwrap();
+        match crossing {
+            crate::Expiry::Expired(lease_state) => {
+                assert_eq!(lease_state.holder(), Some("crashed"))
+            }
+            crossing => panic!("unexpected {:?}", crossing),
+        }
+        write_orphan(ctx, 10).await;
+        let crossing = tokio::time::timeout(Duration::from_secs(3), crossings.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(crossing, crate::Expiry::Live(_)));
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn server_time_source(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_time_source(TimeSource::Server);
+        assert_eq!(lease_lock.time_source(), TimeSource::Local);
+        {
+            let _guard = lease_lock.acquire("a", None).await.unwrap();
+            assert_eq!(lease_lock.time_source(), TimeSource::Server);
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn status_annotations(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(Duration::from_secs(1))
+            .with_status_annotations(true);
+        {
+            let guard = lease_lock.acquire("a", None).await.unwrap();
+            assert_eq!(guard.lease_state().annotations()[HELD_BY_ANNOTATION], "a");
+            assert_eq!(guard.lease_state().annotations()[RENEWALS_ANNOTATION], "0");
+            tokio::time::sleep(Duration::from_millis(900)).await;
+            let lease_state = lease_lock.client.get_state().await.unwrap();
+            assert_ne!(lease_state.annotations()[RENEWALS_ANNOTATION], "0");
+            assert!(lease_state
+                .annotations()
+                .contains_key(EXPIRES_AT_ANNOTATION));
+        }
+        lease_lock.complete_all_operations().await;
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn wait_until_free(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        assert!(matches!(
+            ctx.lease_lock
+                .wait_until_free(Some(Duration::from_millis(300)))
+                .await,
+            Err(Error::AcquireTimeout { .. })
+        ));
+        drop(guard);
+        ctx.lease_lock.complete_all_operations().await;
+        let lease_state = ctx
+            .lease_lock
+            .wait_until_free(Some(Duration::from_secs(1)))
+            .await
+            .unwrap();
+        assert_eq!(lease_state.holder(), None);
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn verify(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        ctx.lease_lock.verify("b").await.unwrap();
+        let lease_state = ctx.lease_lock.client().get_state().await.unwrap();
+        assert_eq!(lease_state.holder(), Some("a"));
+        drop(guard);
     }
 
-    async fn try_overwrite(
-        &self,
-        holder_id: &str,
-        lease_state: LeaseState,
-    ) -> Result<LeaseState, Error> {
-        let now: &str = &chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, false);
-        let patch: LeaseObject = serde_json::from_value(serde_json::json!({
-            "apiVersion": "coordination.k8s.io/v1",
-            "kind": "Lease",
-            "metadata": {
-                "name": &lease_state.lease_name,
-                "resourceVersion": &lease_state.resource_version,
-            },
-            "spec": {
-                "acquireTime": now,
-                "renewTime": now,
-                "holderIdentity": holder_id,
-                "leaseDurationSeconds": self.lease_duration_sec,
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn acquire_until(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        let deadline = Instant::now() + Duration::from_millis(500);
+        match ctx.lease_lock.acquire_until("b", deadline).await {
+            Err(Error::AcquireTimeout { remaining, .. }) => {
+                assert!(remaining <= Duration::from_millis(500))
             }
-        }))?;
+            _ => panic!("expected timeout"),
+        }
+        drop(guard);
+        ctx.lease_lock.complete_all_operations().await;
+        let deadline = Instant::now() + Duration::from_secs(1);
+        ctx.lease_lock.acquire_until("b", deadline).await.unwrap();
+    }
 
-        let patch_res = self
-            .api
-            .patch(
-                &self.lease_name,
-                &PatchParams::apply("lease-rs").force(),
-                &kube::api::Patch::Apply(&patch),
-            )
-            .await;
-        match patch_res {
-            Ok(lease_obj) => Ok(LeaseState::try_from(lease_obj)?),
-            Err(e) => {
-                if let kube::Error::Api(api_err) = e {
-                    if api_err.code == StatusCode::CONFLICT {
-                        log::debug!(
-                            "{}.try_overwrite({}) => conflict",
-                            &self.lease_name,
-                            &holder_id
-                        );
-                        return Ok(lease_state);
-                    }
-                    return Err(kube::Error::Api(api_err).into());
-                }
-                Err(e.into())
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn timeout_history(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        let lease_lock =
+            LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone()).with_timeout_history(3);
+        match lease_lock.acquire("b", Some(Duration::from_secs(2))).await {
+            Err(Error::AcquireTimeout { history, .. }) => {
+                assert!(!history.is_empty() && history.len() <= 3);
+                assert!(history.iter().all(|b| b.holder.as_deref() == Some("a")));
             }
+            _ => panic!("expected timeout"),
         }
+        drop(guard);
     }
-}
 
-type UtcInstant = chrono::DateTime<chrono::offset::Utc>;
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn held_leases(ctx: &mut TestContext) {
+        let held = |name: &str| {
+            crate::held_leases()
+                .iter()
+                .any(|info| info.lease_name == name && info.holder == "a")
+        };
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        assert!(held(&ctx.lease_name));
+        drop(guard);
+        assert!(!held(&ctx.lease_name));
+    }
 
-#[derive(Clone)]
-pub struct LeaseState {
-    lease_name: String,
-    holder: Option<String>,
-    renew_time: UtcInstant,
-    lease_duration: chrono::Duration,
-    resource_version: String,
-}
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn correlation_id(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        let first = guard.correlation_id().to_string();
+        assert!(crate::held_leases()
+            .iter()
+            .any(|info| info.lease_name == ctx.lease_name && info.correlation_id == first));
+        assert!(crate::metrics::render_textfile().contains(&first));
+        guard.release().await.unwrap();
+        assert!(!crate::metrics::render_textfile().contains(&first));
 
-impl TryFrom<LeaseObject> for LeaseState {
-    type Error = crate::lease::Error;
-    fn try_from(lo: LeaseObject) -> Result<Self, Error> {
-        Ok(LeaseState {
-            lease_name: lo
-                .metadata
-                .name
-                .ok_or_else(|| Error::Format("lease name".into()))?,
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        assert_ne!(guard.correlation_id(), first);
+    }
 
-            holder: lo.spec.as_ref().and_then(|x| x.holder_identity.clone()),
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn health_informed_polling(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(Duration::from_secs(1))
+            .with_health_informed_polling(true);
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        assert!(matches!(
+            lease_lock.acquire("b", Some(Duration::from_secs(3))).await,
+            Err(Error::AcquireTimeout { .. })
+        ));
+        drop(guard);
+        lease_lock
+            .acquire("b", Some(Duration::from_secs(3)))
+            .await
+            .unwrap();
+    }
 
-            renew_time: lo
-                .spec
-                .as_ref()
-                .and_then(|x| x.renew_time.as_ref())
-                .map(|x| x.0)
-                .unwrap_or(chrono::MIN_DATETIME),
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn pause_renewal(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(Duration::from_secs(2));
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        guard.pause_renewal();
+        let paused = lease_lock.client.get_state().await.unwrap().renew_time;
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        let lease_state = lease_lock.client.get_state().await.unwrap();
+        assert_eq!(lease_state.renew_time, paused);
+        guard.resume_renewal();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let lease_state = lease_lock.client.get_state().await.unwrap();
+        assert!(lease_state.renew_time > paused);
+    }
 
-            lease_duration: chrono::Duration::seconds(
-                (lo.spec.and_then(|x| x.lease_duration_seconds).unwrap_or(0) as u64)
-                    .try_into()
-                    .map_err(Error::from)?,
-            ),
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn renewals(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(Duration::from_secs(1));
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        let mut renewals = guard.renewals();
+        let acquired = renewals.borrow_and_update().unwrap();
+        tokio::time::timeout(Duration::from_secs(1), renewals.changed())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(renewals.borrow().unwrap() > acquired);
+        drop(guard);
+        assert_eq!(*renewals.borrow(), None);
+    }
 
-            resource_version: lo
-                .metadata
-                .resource_version
-                .ok_or_else(|| Error::Format("resourceVersion".into()))?,
-        })
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn renewer(ctx: &mut TestContext) {
+        write_orphan(ctx, 2).await;
+        assert!(matches!(
+            crate::Renewer::adopt(ctx.api.clone(), &ctx.lease_name, "other").await,
+            Err(Error::Lost)
+        ));
+        let renewer = crate::Renewer::adopt(ctx.api.clone(), &ctx.lease_name, "crashed")
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_secs(3)).await;
+        assert!(renewer.is_held());
+        let lease_state = ctx.lease_lock.client().get_state().await.unwrap();
+        assert_eq!(lease_state.owner(), Some("crashed"));
+        let released = renewer.release().await.unwrap();
+        assert_eq!(released.holder(), None);
     }
-}
 
-impl LeaseState {
-    fn expired(&self) -> bool {
-        self.renew_time + self.lease_duration <= chrono::Utc::now()
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn fencing_token(ctx: &mut TestContext) {
+        let first = ctx.lease_lock.acquire("a", None).await.unwrap();
+        let token = first.fencing_token();
+        drop(first);
+        ctx.lease_lock.complete_all_operations().await;
+        let second = ctx.lease_lock.acquire("b", None).await.unwrap();
+        assert!(second.fencing_token() > token);
     }
 
-    fn owner(&self) -> Option<&str> {
-        if self.expired() {
-            None
-        } else {
-            self.holder.as_deref()
-        }
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn is_valid(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(Duration::from_secs(2));
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        assert!(guard.is_valid());
+        write_orphan(ctx, 10).await;
+        tokio::time::timeout(Duration::from_secs(10), guard.lost())
+            .await
+            .unwrap();
+        assert!(!guard.is_valid());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::lease::*;
-    use futures::stream::StreamExt;
-    use kube::api::{DeleteParams, PostParams};
-    use rand::Rng;
-    use std::sync::Once;
-    use taken::take;
-    use test_context::{test_context, AsyncTestContext};
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn ttl_stream(ctx: &mut TestContext) {
+        let mut ttls = ctx
+            .lease_lock
+            .ttl_stream(Duration::from_millis(100))
+            .boxed();
+        assert_eq!(ttls.next().await, Some(None));
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        let ttl = loop {
+            if let Some(ttl) = ttls.next().await.unwrap() {
+                break ttl;
+            }
+        };
+        assert!(ttl <= guard.lease_state().lease_duration().to_std().unwrap());
+        drop(guard);
+    }
 
-    static LOG_INIT: Once = Once::new();
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn explicit_release(ctx: &mut TestContext) {
+        let guard = ctx.lease_lock.acquire("a", None).await.unwrap();
+        let released = guard.release().await.unwrap();
+        assert_eq!(released.holder(), None);
+        let lease_state = ctx.lease_lock.client().get_state().await.unwrap();
+        assert_eq!(lease_state.holder(), None);
+    }
 
-    struct TestContext {
-        pub lease_name: String,
-        pub api: Api,
-        pub lease_lock: LeaseLock,
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn try_acquire_state(ctx: &mut TestContext) {
+        let guard = match ctx.lease_lock.try_acquire_state("a").await.unwrap() {
+            TryAcquire::Acquired(guard) => guard,
+            TryAcquire::Held(_) => panic!("expected acquire"),
+        };
+        match ctx.lease_lock.try_acquire_state("b").await.unwrap() {
+            TryAcquire::Held(lease_state) => assert_eq!(lease_state.holder(), Some("a")),
+            TryAcquire::Acquired(_) => panic!("expected held"),
+        }
+        drop(guard);
     }
 
-    #[async_trait::async_trait]
-    impl AsyncTestContext for TestContext {
-        async fn setup() -> Self {
-            LOG_INIT.call_once(|| env_logger::init());
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn leader_elector(ctx: &mut TestContext) {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone());
+        let elector = crate::LeaderElector::new(lease_lock, "a")
+            .on_started_leading({
+                let tx = tx.clone();
+                move |_| {
+                    let tx = tx.clone();
+                    async move {
+                        let _ = tx.send("started".to_string());
+                    }
+                }
+            })
+            .on_new_leader({
+                let tx = tx.clone();
+                move |leader| {
+                    let _ = tx.send(format!("leader {}", leader));
+                }
+            })
+            .on_stopped_leading(move || {
+                let _ = tx.send("stopped".to_string());
+            });
+        assert!(tokio::time::timeout(Duration::from_secs(2), elector.run())
+            .await
+            .is_err());
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        assert!(events.contains(&"started".to_string()));
+        assert!(events.contains(&"leader a".to_string()));
+        assert_eq!(events.last().map(String::as_str), Some("stopped"));
+    }
 
-            let lease_name = format!("test-lease-{}", rand::thread_rng().gen::<u32>());
-            log::debug!("{}.setup()", &lease_name);
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn leader_elector_standby(ctx: &mut TestContext) {
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone());
+        let elector = crate::LeaderElector::new(lease_lock, "a");
+        elector.set_enabled(false);
+        let mut role = elector.role();
+        let run = elector.run();
+        tokio::pin!(run);
+        tokio::select! {
+            _ = &mut run => panic!("election stopped"),
+            _ = tokio::time::sleep(Duration::from_secs(1)) => {}
+        }
+        assert_eq!(*role.borrow(), crate::Role::Standby);
+        test_util::assert_held_by(&ctx.api, &ctx.lease_name, None).await;
 
-            let api = kube::Api::default_namespaced(kube::Client::try_default().await.unwrap());
-            let lease: LeaseObject = serde_json::from_value(serde_json::json!({
-                "apiVersion": "coordination.k8s.io/v1",
-                "kind": "Lease",
-                "metadata": { "name": &lease_name },
-                "spec": {},
-            }))
-            .unwrap();
-            let _ = api.create(&PostParams::default(), &lease).await;
-            let lease_lock = LeaseLock::new(api.clone(), lease_name.clone());
-            Self {
-                lease_name,
-                api,
-                lease_lock,
+        elector.set_enabled(true);
+        let leading = async {
+            while !matches!(*role.borrow_and_update(), crate::Role::Leader { .. }) {
+                role.changed().await.unwrap();
             }
+        };
+        tokio::select! {
+            _ = &mut run => panic!("election stopped"),
+            _ = tokio::time::timeout(Duration::from_secs(5), leading) => {}
         }
+        test_util::assert_held_by(&ctx.api, &ctx.lease_name, Some("a")).await;
 
-        async fn teardown(mut self) {
-            log::debug!("{}.teardown()", &self.lease_name);
-            self.lease_lock.complete_all_operations().await;
-            self.api
-                .delete(&self.lease_name, &DeleteParams::default())
-                .await
-                .unwrap();
+        elector.set_enabled(false);
+        tokio::select! {
+            _ = &mut run => panic!("election stopped"),
+            _ = tokio::time::sleep(Duration::from_secs(1)) => {}
         }
+        assert_eq!(*elector.role().borrow(), crate::Role::Standby);
+        test_util::assert_held_by(&ctx.api, &ctx.lease_name, None).await;
     }
 
     #[test_context(TestContext)]
     #[tokio::test]
-    async fn raii(ctx: &mut TestContext) {
-        {
-            let _guard = ctx
-                .lease_lock
-                .try_acquire("initial")
-                .await
-                .unwrap()
-                .unwrap();
-            assert!(ctx
-                .lease_lock
-                .try_acquire("within scope")
-                .await
-                .unwrap()
-                .is_none());
-        }
-        ctx.lease_lock
-            .acquire("outside scope", Some(Duration::from_secs(1)))
+    async fn renewal_client(ctx: &mut TestContext) {
+        let client = kube::Client::try_default().await.unwrap();
+        let lease_lock = LeaseLock::new(ctx.api.clone(), ctx.lease_name.clone())
+            .with_lease_duration(Duration::from_secs(1))
+            .with_renewal_client(client);
+        let guard = lease_lock.acquire("a", None).await.unwrap();
+        let mut renewals = guard.renewals();
+        renewals.borrow_and_update();
+        tokio::time::timeout(Duration::from_secs(1), renewals.changed())
             .await
+            .unwrap()
             .unwrap();
+        assert!(guard.is_held());
+    }
+
+    struct RetryTwice(std::sync::Mutex<Vec<(Operation, u32)>>);
+
+    impl RetryPolicy for RetryTwice {
+        fn on_error(&self, op: Operation, _: &Error, attempt: u32, _: Duration) -> RetryDecision {
+            self.0.lock().unwrap().push((op, attempt));
+            match attempt {
+                1 | 2 => RetryDecision::Retry(Duration::from_millis(10)),
+                _ => RetryDecision::Fail,
+            }
+        }
     }
 
     #[test_context(TestContext)]
     #[tokio::test]
-    async fn concurrent_locks(ctx: &mut TestContext) {
-        use std::sync::Arc;
-        use tokio::sync::Mutex;
-        let glob = Arc::new(Mutex::new(0));
-        take!(&glob, &ctx);
-        (1..8)
-            .map(|i| async move {
-                let _guard = ctx
-                    .lease_lock
-                    .acquire(&format!("{}", i), Some(Duration::from_secs(20)))
-                    .await
-                    .unwrap();
-                *glob.lock().await = i;
-                tokio::time::sleep(Duration::from_millis(10)).await;
-                assert_eq!(*glob.lock().await, i);
-            })
-            .collect::<futures::stream::FuturesUnordered<_>>()
-            .collect::<Vec<_>>()
-            .await;
+    async fn retry_policy(ctx: &mut TestContext) {
+        let policy = Arc::new(RetryTwice(Default::default()));
+        let missing = format!("{}-missing", &ctx.lease_name);
+        let lease_lock = LeaseLock::new(ctx.api.clone(), missing).with_retry_policy(policy.clone());
+        assert!(matches!(
+            lease_lock.acquire("a", None).await,
+            Err(Error::Kube(_))
+        ));
+        let calls = policy.0.lock().unwrap().clone();
+        assert_eq!(
+            calls,
+            vec![
+                (Operation::Acquire, 1),
+                (Operation::Acquire, 2),
+                (Operation::Acquire, 3)
+            ]
+        );
     }
 
     #[test_context(TestContext)]
     #[tokio::test]
-    async fn complete(ctx: &mut TestContext) {
-        {
-            let _ = ctx.lease_lock.try_acquire("1").await.unwrap().unwrap();
-        }
-        ctx.lease_lock.complete_all_operations().await;
-        {
-            let _ = ctx.lease_lock.try_acquire("1").await.unwrap().unwrap();
-        }
+    async fn error_observer(ctx: &mut TestContext) {
+        let observed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let missing = format!("{}-missing", &ctx.lease_name);
+        let lease_lock = LeaseLock::new(ctx.api.clone(), missing).with_error_observer({
+            let observed = observed.clone();
+            move |_: &Error, op| observed.lock().unwrap().push(op)
+        });
+        assert!(lease_lock.try_acquire("a").await.is_err());
+        assert_eq!(*observed.lock().unwrap(), vec![Operation::Acquire]);
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn job_guard(ctx: &mut TestContext) {
+        use crate::job::JobGuard;
+        let job_name = format!("{}-job", &ctx.lease_name);
+        let period = Duration::from_secs(3600);
+        let claim =
+            |holder: &'static str| JobGuard::claim(ctx.api.clone(), &job_name, period, holder);
+        let stale: LeaseObject = serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "name": format!("{}-0", &job_name),
+                "labels": { crate::consts::JOB_LABEL: &job_name },
+            },
+            "spec": {},
+        }))
+        .unwrap();
+        ctx.api
+            .create(&PostParams::default(), &stale)
+            .await
+            .unwrap();
+        let job = claim("a").await.unwrap().unwrap();
+        assert!(matches!(
+            ctx.api.get(&format!("{}-0", &job_name)).await,
+            Err(kube::Error::Api(api_err)) if api_err.code == StatusCode::NOT_FOUND
+        ));
+        assert!(claim("b").await.unwrap().is_none());
+        job.complete().await.unwrap();
+        assert!(claim("b").await.unwrap().is_none());
+        ctx.api
+            .delete_collection(
+                &DeleteParams::default(),
+                &kube::api::ListParams::default()
+                    .labels(&format!("rust-kube-lease/job={}", &job_name)),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[test_context(TestContext)]
+    #[tokio::test]
+    async fn audit(ctx: &mut TestContext) {
+        use crate::audit::{AuditEvent, LeaseAuditor};
+        let auditor = LeaseAuditor::new(ctx.api.clone(), vec![ctx.lease_name.clone()]);
+        let mut events = auditor.events().boxed();
+        // let the watch observe the initial state
+        let _ = tokio::time::timeout(Duration::from_secs(1), events.next()).await;
+
+        let guard = ctx
+            .lease_lock
+            .try_acquire("audited")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            tokio::time::timeout(Duration::from_secs(5), events.next())
+                .await
+                .unwrap()
+                .unwrap(),
+            AuditEvent::Transition {
+                lease_name: ctx.lease_name.clone(),
+                from: None,
+                to: Some("audited".into()),
+            }
+        );
+        drop(guard);
+        assert_eq!(
+            tokio::time::timeout(Duration::from_secs(5), events.next())
+                .await
+                .unwrap()
+                .unwrap(),
+            AuditEvent::Transition {
+                lease_name: ctx.lease_name.clone(),
+                from: Some("audited".into()),
+                to: None,
+            }
+        );
     }
 
     #[test_context(TestContext)]
@@ -554,7 +5084,7 @@ mod tests {
         ctx.api
             .patch(
                 &ctx.lease_lock.client.lease_name,
-                &PatchParams::apply("lease-rs").force(),
+                &PatchParams::apply(crate::consts::DEFAULT_FIELD_MANAGER).force(),
                 &kube::api::Patch::Apply(&patch),
             )
             .await
@@ -1,6 +1,92 @@
 #![deny(unsafe_code)]
 
+mod audit;
+mod bulk;
+mod cache;
+mod client;
+mod cluster;
+pub mod consts;
+mod contention;
+mod data;
+mod defaults;
+mod election;
+mod expiry;
+mod fence;
+mod guarded;
+mod job;
+mod leadership;
 mod lease;
+#[cfg(feature = "debug-lock-order")]
+mod lock_order;
+pub mod metrics;
+mod mutex;
+mod node;
+mod observe;
+mod oneshot;
+pub mod ops;
+#[cfg(feature = "otel")]
+mod otel;
+mod pending;
+mod persist;
+mod random;
+mod reaper;
+mod registry;
+mod release;
+mod renewer;
+mod resourcelock;
+mod retry;
+mod role;
+mod schema;
+mod sharded;
+mod shared;
+#[cfg(feature = "sidecar")]
+pub mod sidecar;
+#[cfg(any(test, feature = "test-util"))]
+pub mod simulation;
+mod status;
+mod task;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+mod tiered;
+mod token;
+mod tuning;
+mod watch;
 
-pub use lease::{Error, LeaseLock, LeaseGuard};
-
+pub use audit::{AuditEvent, LeaseAuditor};
+pub use bulk::BulkWatch;
+pub use client::LeaseClient;
+pub use cluster::{ClusterLock, DEFAULT_CLUSTER_NAMESPACE};
+pub use contention::{ContentionLevel, Priority};
+pub use data::DataGuard;
+pub use defaults::{defaults, Defaults};
+pub use election::LeaderElector;
+pub use expiry::{expiry_stream, Expiry};
+pub use fence::{BoxError, FencedResource};
+pub use job::JobGuard;
+pub use leadership::{require_leadership, NotLeader};
+pub use lease::{
+    Breadcrumb, Decision, Error, LeaseGuard, LeaseLock, LeaseState, OutageMode, Takeover,
+    TimeSource, TryAcquire,
+};
+pub use mutex::AsyncDistributedMutex;
+pub use node::NodeLock;
+pub use observe::LeaseObserver;
+pub use oneshot::lock;
+pub use pending::PendingAcquire;
+pub use random::{RandomSource, SeededRandom, ThreadRandom};
+pub use reaper::Reaper;
+pub use registry::{held_leases, HeldInfo};
+pub use release::release_all;
+pub use renewer::Renewer;
+pub use resourcelock::{
+    try_acquire_or_renew, try_acquire_or_renew_at, AnnotationLock, ConfigMapLock, EndpointsLock,
+    LeaderElectionRecord, LeaseResourceLock, ResourceLock,
+};
+pub use retry::{Operation, RetryDecision, RetryPolicy};
+pub use role::Role;
+pub use schema::SCHEMA_VERSION;
+pub use sharded::ShardedLocks;
+pub use shared::{SharedLeaseGuard, SharedLeaseLock};
+pub use status::ConditionReporter;
+pub use tiered::{TieredGuard, TieredLock};
+pub use token::GuardToken;
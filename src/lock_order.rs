@@ -0,0 +1,82 @@
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// Leases currently held, per holder id. Orders are tracked per holder id rather than per task:
+/// guards are released from whichever task drops them, and one holder id is usually one logical
+/// actor. Tasks sharing a holder id are checked as one; give concurrent tasks their own holder ids
+/// to check them separately.
+static HELD: Lazy<Mutex<HashMap<String, Vec<String>>>> = Lazy::new(Default::default);
+
+/// Observed acquisition orders: `(a, b)` means `b` was acquired while holding `a`.
+static ORDERS: Lazy<Mutex<HashSet<(String, String)>>> = Lazy::new(Default::default);
+
+/// Check that acquiring `lease_name` by `holder_id` is consistent with every order observed
+/// before in this process, and remember the new orders. Panics on inversion, which could
+/// deadlock with another holder taking the same leases in the opposite order.
+pub(crate) fn check_acquire(holder_id: &str, lease_name: &str) {
+    let inverted = {
+        let held = HELD.lock().unwrap();
+        let mut orders = ORDERS.lock().unwrap();
+        let mut inverted = None;
+        for prior in held.get(holder_id).into_iter().flatten() {
+            if orders.contains(&(lease_name.to_string(), prior.clone())) {
+                inverted = Some(prior.clone());
+                break;
+            }
+            orders.insert((prior.clone(), lease_name.to_string()));
+        }
+        inverted
+    };
+    // panic with the locks released, so that they aren't poisoned for other holders
+    if let Some(prior) = inverted {
+        log::error!(
+            "{}.acquire({}) while holding {}: inconsistent lock order",
+            lease_name,
+            holder_id,
+            prior
+        );
+        panic!(
+            "lock order inversion: {} acquired while holding {}, but also observed the opposite order",
+            lease_name, prior
+        );
+    }
+}
+
+pub(crate) fn acquired(holder_id: &str, lease_name: &str) {
+    HELD.lock()
+        .unwrap()
+        .entry(holder_id.to_string())
+        .or_default()
+        .push(lease_name.to_string());
+}
+
+pub(crate) fn released(holder_id: &str, lease_name: &str) {
+    let mut held = HELD.lock().unwrap();
+    if let Some(leases) = held.get_mut(holder_id) {
+        leases.retain(|l| l != lease_name);
+        if leases.is_empty() {
+            held.remove(holder_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lock_order::*;
+
+    #[test]
+    #[should_panic(expected = "lock order inversion")]
+    fn inversion() {
+        check_acquire("order-test", "order-a");
+        acquired("order-test", "order-a");
+        check_acquire("order-test", "order-b");
+        acquired("order-test", "order-b");
+        released("order-test", "order-b");
+        released("order-test", "order-a");
+
+        check_acquire("order-test", "order-b");
+        acquired("order-test", "order-b");
+        check_acquire("order-test", "order-a");
+    }
+}
@@ -0,0 +1,152 @@
+//! Process-wide lease metrics, for export without an HTTP exporter.
+
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::sync::Mutex;
+
+static METRICS: Lazy<Mutex<BTreeMap<String, LeaseMetrics>>> = Lazy::new(Default::default);
+
+/// Counters of a lease in this process.
+#[derive(Default)]
+pub(crate) struct LeaseMetrics {
+    pub(crate) held: i64,
+    pub(crate) acquires: u64,
+    pub(crate) conflicts: u64,
+    pub(crate) renewals: u64,
+    pub(crate) renewal_errors: u64,
+    pub(crate) lost: u64,
+    pub(crate) stale_retries: u64,
+    /// Label of the [Role](crate::Role) of this process, if it takes part in an election.
+    pub(crate) role: Option<&'static str>,
+    /// Correlation ids of the guards of the lease held by this process.
+    pub(crate) correlation_ids: BTreeSet<String>,
+}
+
+pub(crate) fn record(lease_name: &str, update: impl FnOnce(&mut LeaseMetrics)) {
+    let mut metrics = METRICS.lock().unwrap();
+    update(metrics.entry(lease_name.to_string()).or_default());
+}
+
+/// Metric family: name, type, help and how to read it from a lease's metrics.
+type Family = (
+    &'static str,
+    &'static str,
+    &'static str,
+    fn(&LeaseMetrics) -> i64,
+);
+
+/// Current metrics of all leases used by this process, in Prometheus exposition format,
+/// e.g. to be written periodically for the node exporter's textfile collector.
+pub fn render_textfile() -> String {
+    let metrics = METRICS.lock().unwrap();
+    let families: [Family; 7] = [
+        (
+            "kube_lease_held",
+            "gauge",
+            "Guards of the lease held by this process.",
+            |m| m.held,
+        ),
+        (
+            "kube_lease_acquires_total",
+            "counter",
+            "Successful acquires.",
+            |m| m.acquires as i64,
+        ),
+        (
+            "kube_lease_conflicts_total",
+            "counter",
+            "Takeover attempts lost to another writer.",
+            |m| m.conflicts as i64,
+        ),
+        (
+            "kube_lease_renewals_total",
+            "counter",
+            "Successful renewals.",
+            |m| m.renewals as i64,
+        ),
+        (
+            "kube_lease_renewal_errors_total",
+            "counter",
+            "Failed renewals.",
+            |m| m.renewal_errors as i64,
+        ),
+        (
+            "kube_lease_lost_total",
+            "counter",
+            "Leases found taken over while held.",
+            |m| m.lost as i64,
+        ),
+        (
+            "kube_lease_stale_resource_version_retries_total",
+            "counter",
+            "Takeovers retried after conflicting on a stale resourceVersion.",
+            |m| m.stale_retries as i64,
+        ),
+    ];
+    let mut out = String::new();
+    for (name, kind, help, value) in families {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} {}", name, kind);
+        for (lease_name, lease_metrics) in metrics.iter() {
+            let _ = writeln!(
+                out,
+                "{}{{lease=\"{}\"}} {}",
+                name,
+                lease_name,
+                value(lease_metrics)
+            );
+        }
+    }
+    let _ = writeln!(
+        out,
+        "# HELP kube_lease_role Role of this process in the election for the lease."
+    );
+    let _ = writeln!(out, "# TYPE kube_lease_role gauge");
+    for (lease_name, lease_metrics) in metrics.iter() {
+        if let Some(role) = lease_metrics.role {
+            let _ = writeln!(
+                out,
+                "kube_lease_role{{lease=\"{}\",role=\"{}\"}} 1",
+                lease_name, role
+            );
+        }
+    }
+    let _ = writeln!(
+        out,
+        "# HELP kube_lease_guard Guard of the lease held by this process, by correlation id."
+    );
+    let _ = writeln!(out, "# TYPE kube_lease_guard gauge");
+    for (lease_name, lease_metrics) in metrics.iter() {
+        for correlation_id in &lease_metrics.correlation_ids {
+            let _ = writeln!(
+                out,
+                "kube_lease_guard{{lease=\"{}\",correlation_id=\"{}\"}} 1",
+                lease_name, correlation_id
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::*;
+
+    #[test]
+    fn render() {
+        record("metrics-test", |m| {
+            m.held += 1;
+            m.acquires += 1;
+            m.role = Some("leader");
+            m.correlation_ids.insert("00c0ffee".into());
+        });
+        let text = render_textfile();
+        assert!(text.contains("# TYPE kube_lease_held gauge\n"));
+        assert!(text.contains("kube_lease_held{lease=\"metrics-test\"} 1\n"));
+        assert!(text.contains("kube_lease_acquires_total{lease=\"metrics-test\"} 1\n"));
+        assert!(text.contains("kube_lease_role{lease=\"metrics-test\",role=\"leader\"} 1\n"));
+        assert!(text
+            .contains("kube_lease_guard{lease=\"metrics-test\",correlation_id=\"00c0ffee\"} 1\n"));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::lease::{Error, LeaseGuard, LeaseLock};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// Mutex acquired asynchronously on behalf of a holder, so that code can abstract over
+/// in-process and distributed locks. Implemented by [LeaseLock] and, for tests,
+/// by `Arc<tokio::sync::Mutex<()>>`, which ignores holders.
+pub trait AsyncDistributedMutex: Send + Sync {
+    /// Held while alive; dropping it releases the mutex.
+    type Guard: Send;
+
+    /// Wait for the mutex at most `timeout`, or forever if None.
+    /// Return [Error::AcquireTimeout] if it was not acquired in time.
+    fn acquire<'a>(
+        &'a self,
+        holder_id: &'a str,
+        timeout: Option<Duration>,
+    ) -> BoxFuture<'a, Result<Self::Guard, Error>>;
+
+    /// Acquire the mutex if it can be done immediately. If not, return None.
+    fn try_acquire<'a>(
+        &'a self,
+        holder_id: &'a str,
+    ) -> BoxFuture<'a, Result<Option<Self::Guard>, Error>>;
+
+    /// Release the mutex.
+    fn release(&self, guard: Self::Guard) {
+        drop(guard);
+    }
+}
+
+impl AsyncDistributedMutex for LeaseLock {
+    type Guard = LeaseGuard;
+
+    fn acquire<'a>(
+        &'a self,
+        holder_id: &'a str,
+        timeout: Option<Duration>,
+    ) -> BoxFuture<'a, Result<LeaseGuard, Error>> {
+        LeaseLock::acquire(self, holder_id, timeout).boxed()
+    }
+
+    fn try_acquire<'a>(
+        &'a self,
+        holder_id: &'a str,
+    ) -> BoxFuture<'a, Result<Option<LeaseGuard>, Error>> {
+        LeaseLock::try_acquire(self, holder_id).boxed()
+    }
+}
+
+impl AsyncDistributedMutex for Arc<Mutex<()>> {
+    type Guard = OwnedMutexGuard<()>;
+
+    fn acquire<'a>(
+        &'a self,
+        _holder_id: &'a str,
+        timeout: Option<Duration>,
+    ) -> BoxFuture<'a, Result<OwnedMutexGuard<()>, Error>> {
+        async move {
+            let lock = self.clone().lock_owned();
+            match timeout {
+                None => Ok(lock.await),
+                Some(to) => {
+                    tokio::time::timeout(to, lock)
+                        .await
+                        .map_err(|_| Error::AcquireTimeout {
+                            remaining: Duration::ZERO,
+                            history: Vec::new(),
+                        })
+                }
+            }
+        }
+        .boxed()
+    }
+
+    fn try_acquire<'a>(
+        &'a self,
+        _holder_id: &'a str,
+    ) -> BoxFuture<'a, Result<Option<OwnedMutexGuard<()>>, Error>> {
+        futures::future::ready(Ok(self.clone().try_lock_owned().ok())).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mutex::*;
+
+    async fn exclusive<M: AsyncDistributedMutex>(mutex: &M) {
+        let guard = mutex.acquire("a", None).await.unwrap();
+        assert!(mutex.try_acquire("b").await.unwrap().is_none());
+        assert!(matches!(
+            mutex.acquire("b", Some(Duration::from_millis(10))).await,
+            Err(Error::AcquireTimeout { .. })
+        ));
+        mutex.release(guard);
+        assert!(mutex.try_acquire("b").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn local_mutex() {
+        exclusive(&Arc::new(Mutex::new(()))).await;
+    }
+}
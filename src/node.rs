@@ -0,0 +1,67 @@
+use crate::lease::{Error, LeaseGuard, LeaseLock};
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use std::time::Duration;
+
+/// Environment variable with the name of the node this process runs on, to be set from
+/// `spec.nodeName` via the downward API.
+const NODE_NAME_ENV: &str = "NODE_NAME";
+
+/// Per-node singleton lock, e.g. for one active scraper per node across DaemonSet restarts.
+/// The lease is named `<prefix>-<node>`, and may only be used by processes running on that node.
+pub struct NodeLock {
+    lease_lock: LeaseLock,
+    node_name: String,
+}
+
+impl NodeLock {
+    /// Lock of the node this process runs on, as told by the `NODE_NAME` environment variable.
+    pub fn new(api: kube::Api<LeaseObject>, prefix: &str) -> Result<Self, Error> {
+        let node_name = std::env::var(NODE_NAME_ENV)
+            .map_err(|_| Error::Config(format!("{} is not set", NODE_NAME_ENV)))?;
+        Ok(Self {
+            lease_lock: LeaseLock::new(api, format!("{}-{}", prefix, &node_name))
+                .with_create_if_missing(true),
+            node_name,
+        })
+    }
+
+    /// Lock of `node_name`, which must be the node this process runs on.
+    pub fn for_node(
+        api: kube::Api<LeaseObject>,
+        prefix: &str,
+        node_name: &str,
+    ) -> Result<Self, Error> {
+        let node_lock = Self::new(api, prefix)?;
+        if node_lock.node_name != node_name {
+            return Err(Error::Config(format!(
+                "running on node {}, not {}",
+                &node_lock.node_name, node_name
+            )));
+        }
+        Ok(node_lock)
+    }
+
+    /// Configure the underlying [LeaseLock], e.g. `|l| l.with_lease_duration_sec(30)`.
+    pub fn configure(mut self, configure: impl FnOnce(LeaseLock) -> LeaseLock) -> Self {
+        self.lease_lock = configure(self.lease_lock);
+        self
+    }
+
+    pub fn node_name(&self) -> &str {
+        &self.node_name
+    }
+
+    /// Acquire the lock like [LeaseLock::acquire], creating the lease of the node if missing.
+    pub async fn acquire(
+        &self,
+        holder_id: &str,
+        acquire_timeout: Option<Duration>,
+    ) -> Result<LeaseGuard, Error> {
+        self.lease_lock.acquire(holder_id, acquire_timeout).await
+    }
+
+    /// See [LeaseLock::complete_all_operations].
+    pub async fn complete_all_operations(&self) {
+        self.lease_lock.complete_all_operations().await;
+    }
+}
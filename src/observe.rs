@@ -0,0 +1,31 @@
+use crate::lease::{Error, LeaseLock, LeaseLockClient, LeaseState};
+use futures::Stream;
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use std::time::Duration;
+
+/// Read-only view of a lease: its holder and expiry, and notifications of changes. Unlike
+/// [LeaseLock], it has no way to write the lease, for components that must never grab the lock.
+#[derive(Clone)]
+pub struct LeaseObserver {
+    client: LeaseLockClient,
+}
+
+impl LeaseObserver {
+    pub fn new(api: kube::Api<LeaseObject>, lease_name: String) -> Self {
+        LeaseLock::new(api, lease_name).observe()
+    }
+
+    pub(crate) fn from_client(client: LeaseLockClient) -> Self {
+        Self { client }
+    }
+
+    /// State of the lease no older than `max_staleness`, see [LeaseLock::current_leader].
+    pub async fn state(&self, max_staleness: Duration) -> Result<LeaseState, Error> {
+        self.client.current_leader(max_staleness).await
+    }
+
+    /// Endless stream of states of the lease as seen by a watch, see [LeaseLock::watch].
+    pub fn changes(&self) -> impl Stream<Item = LeaseState> + Send + 'static {
+        self.client.watch()
+    }
+}
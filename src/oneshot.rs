@@ -0,0 +1,32 @@
+use crate::lease::{Error, LeaseLock};
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use std::future::Future;
+use std::time::Duration;
+
+/// Run `fut` while holding lease `lease_name` in `namespace`: create the lease if missing,
+/// acquire it as `holder_id` (waiting up to `acquire_timeout`), run, then release and wait
+/// for the release to complete. Meant for scripts and Jobs that need "exactly once across
+/// replicas" without managing a [LeaseLock].
+pub async fn lock<F, T>(
+    client: kube::Client,
+    namespace: &str,
+    lease_name: &str,
+    holder_id: &str,
+    acquire_timeout: Option<Duration>,
+    fut: F,
+) -> Result<T, Error>
+where
+    F: Future<Output = T>,
+{
+    let api = kube::Api::<LeaseObject>::namespaced(client, namespace);
+    let lease_lock = LeaseLock::new(api, lease_name.to_string());
+    lease_lock
+        .client()
+        .create_if_missing(&Default::default())
+        .await?;
+    let guard = lease_lock.acquire(holder_id, acquire_timeout).await?;
+    let output = fut.await;
+    drop(guard);
+    lease_lock.complete_all_operations().await;
+    Ok(output)
+}
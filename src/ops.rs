@@ -0,0 +1,109 @@
+use crate::consts::{READY_SUCCESSOR_ANNOTATION, RESTART_EPOCH_ANNOTATION};
+use crate::lease::{Error, LeaseGuard, LeaseLock};
+use futures::stream::StreamExt;
+use std::time::Duration;
+
+/// Outcome of [orchestrate_restart].
+// Short-lived and matched on right away, so the guard isn't boxed.
+#[allow(clippy::large_enum_variant)]
+pub enum Handover {
+    /// A healthy successor announced itself and the lease was released for it.
+    Done { successor: String },
+    /// No successor took over; the leader keeps leading.
+    Kept { guard: LeaseGuard, reason: Error },
+}
+
+/// Step down for a rollout without a leadership gap: mark the lease with a new restart epoch,
+/// wait until a successor announces readiness for it (see [announce_ready]), and only then
+/// release the lease. If no successor shows up within `timeout`, the guard is handed back.
+pub async fn orchestrate_restart(guard: LeaseGuard, timeout: Duration) -> Handover {
+    let epoch = chrono::Utc::now().timestamp_millis().to_string();
+    let successor = wait_successor(&guard, &epoch, timeout).await;
+    if let Err(e) = guard
+        .client()
+        .annotate(RESTART_EPOCH_ANNOTATION, None)
+        .await
+    {
+        log::error!(
+            "{}.orchestrate_restart({}) => {}",
+            guard.lease_state().lease_name(),
+            &epoch,
+            e
+        );
+    }
+    match successor {
+        Ok(successor) => {
+            log::info!(
+                "{}.orchestrate_restart({}) => handing over to {}",
+                guard.lease_state().lease_name(),
+                &epoch,
+                &successor
+            );
+            drop(guard);
+            Handover::Done { successor }
+        }
+        Err(reason) => Handover::Kept { guard, reason },
+    }
+}
+
+async fn wait_successor(
+    guard: &LeaseGuard,
+    epoch: &str,
+    timeout: Duration,
+) -> Result<String, Error> {
+    let client = guard.client();
+    let own = guard.lease_state().holder().unwrap_or_default();
+    let mut states = client.watch().boxed();
+    client
+        .annotate(RESTART_EPOCH_ANNOTATION, Some(epoch))
+        .await?;
+    let successor = tokio::time::timeout(timeout, async {
+        while let Some(lease_state) = states.next().await {
+            let ready = lease_state
+                .annotations()
+                .get(READY_SUCCESSOR_ANNOTATION)
+                .and_then(|ready| ready.split_once('/'));
+            if let Some((ready_epoch, successor)) = ready {
+                if ready_epoch == epoch && successor != own {
+                    return Some(successor.to_string());
+                }
+            }
+        }
+        None
+    })
+    .await;
+    match successor {
+        Ok(Some(successor)) => Ok(successor),
+        _ => Err(Error::HandoverTimeout),
+    }
+}
+
+/// Called by a new replica once it is healthy: wait for the leader to start a restart
+/// (see [orchestrate_restart]) and announce `holder_id` as a ready successor for it.
+/// Afterwards, the replica should [LeaseLock::acquire] as usual.
+pub async fn announce_ready(
+    lease_lock: &LeaseLock,
+    holder_id: &str,
+    timeout: Duration,
+) -> Result<(), Error> {
+    let client = lease_lock.client();
+    let mut states = client.watch().boxed();
+    let epoch = tokio::time::timeout(timeout, async {
+        while let Some(lease_state) = states.next().await {
+            if let Some(epoch) = lease_state.annotations().get(RESTART_EPOCH_ANNOTATION) {
+                return Some(epoch.clone());
+            }
+        }
+        None
+    })
+    .await;
+    let epoch = match epoch {
+        Ok(Some(epoch)) => epoch,
+        _ => return Err(Error::HandoverTimeout),
+    };
+    let ready = format!("{}/{}", epoch, client.holder_identity(holder_id));
+    client
+        .annotate(READY_SUCCESSOR_ANNOTATION, Some(&ready))
+        .await?;
+    Ok(())
+}
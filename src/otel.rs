@@ -0,0 +1,105 @@
+//! Election transitions as OpenTelemetry counters and span events, enabled by the `otel`
+//! feature. They go through the global meter and the active span, so they end up in the
+//! user's existing OTLP pipeline.
+
+use once_cell::sync::Lazy;
+use opentelemetry::metrics::Counter;
+use opentelemetry::trace::get_active_span;
+use opentelemetry::KeyValue;
+
+struct Counters {
+    acquires: Counter<u64>,
+    lost: Counter<u64>,
+    renew_failures: Counter<u64>,
+    outage_stops: Counter<u64>,
+}
+
+static COUNTERS: Lazy<Counters> = Lazy::new(|| {
+    let meter = opentelemetry::global::meter("rust-kube-lease");
+    Counters {
+        acquires: meter
+            .u64_counter("kube_lease.acquires")
+            .with_description("Successful acquires.")
+            .init(),
+        lost: meter
+            .u64_counter("kube_lease.lost")
+            .with_description("Leases found taken over while held.")
+            .init(),
+        renew_failures: meter
+            .u64_counter("kube_lease.renew_failures")
+            .with_description("Failed renewals.")
+            .init(),
+        outage_stops: meter
+            .u64_counter("kube_lease.outage_stops")
+            .with_description("Leases given up because the API server was unreachable.")
+            .init(),
+    }
+});
+
+fn emit(
+    counter: &Counter<u64>,
+    event: &'static str,
+    lease_name: &str,
+    holder: &str,
+    correlation_id: &str,
+) {
+    let attributes = [
+        KeyValue::new("lease", lease_name.to_string()),
+        KeyValue::new("holder", holder.to_string()),
+    ];
+    counter.add(1, &attributes);
+    // only on span events: a label per acquisition would blow up the cardinality of counters
+    let mut event_attributes = attributes.to_vec();
+    event_attributes.push(KeyValue::new("correlation_id", correlation_id.to_string()));
+    get_active_span(|span| span.add_event(event, event_attributes));
+}
+
+pub(crate) fn acquired(lease_name: &str, holder: &str, correlation_id: &str) {
+    emit(
+        &COUNTERS.acquires,
+        "lease.acquired",
+        lease_name,
+        holder,
+        correlation_id,
+    );
+}
+
+pub(crate) fn lost(lease_name: &str, holder: &str, correlation_id: &str) {
+    emit(
+        &COUNTERS.lost,
+        "lease.lost",
+        lease_name,
+        holder,
+        correlation_id,
+    );
+}
+
+pub(crate) fn renew_failed(lease_name: &str, holder: &str, correlation_id: &str) {
+    emit(
+        &COUNTERS.renew_failures,
+        "lease.renew_failed",
+        lease_name,
+        holder,
+        correlation_id,
+    );
+}
+
+pub(crate) fn outage_expired(lease_name: &str, holder: &str, correlation_id: &str) {
+    emit(
+        &COUNTERS.outage_stops,
+        "lease.outage_expired",
+        lease_name,
+        holder,
+        correlation_id,
+    );
+}
+
+pub(crate) fn outage_stopped(lease_name: &str, holder: &str, correlation_id: &str) {
+    emit(
+        &COUNTERS.outage_stops,
+        "lease.outage_stopped",
+        lease_name,
+        holder,
+        correlation_id,
+    );
+}
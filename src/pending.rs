@@ -0,0 +1,73 @@
+use crate::consts::PENDING_HOLDER_ANNOTATION;
+use crate::lease::{Error, LeaseGuard, LeaseLock};
+use std::time::Duration;
+
+/// Announced intent to acquire a lease, see [LeaseLock::prepare_acquire]. Either
+/// [commit](PendingAcquire::commit) or [cancel](PendingAcquire::cancel) it; a dropped intent
+/// stays on the lease until the next one replaces it.
+pub struct PendingAcquire<'a> {
+    lease_lock: &'a LeaseLock,
+    holder_id: String,
+}
+
+impl LeaseLock {
+    /// First phase of a two-phase acquire: announce `holder_id` as the pending holder on the
+    /// lease, visible to observers (see
+    /// [LeaseState::pending_holder](crate::LeaseState::pending_holder)), without taking the lease.
+    /// Fleets can run approval or quorum checks before committing.
+    pub async fn prepare_acquire(&self, holder_id: &str) -> Result<PendingAcquire<'_>, Error> {
+        let client = self.client();
+        let identity = client.holder_identity(holder_id);
+        client
+            .annotate(PENDING_HOLDER_ANNOTATION, Some(&identity))
+            .await?;
+        log::debug!(
+            "{}.prepare_acquire({}) => OK",
+            client.lease_name(),
+            holder_id
+        );
+        Ok(PendingAcquire {
+            lease_lock: self,
+            holder_id: holder_id.to_string(),
+        })
+    }
+}
+
+impl PendingAcquire<'_> {
+    pub fn holder_id(&self) -> &str {
+        &self.holder_id
+    }
+
+    /// Second phase: acquire the lease as with [LeaseLock::acquire], then withdraw the intent.
+    pub async fn commit(self, acquire_timeout: Option<Duration>) -> Result<LeaseGuard, Error> {
+        let acquired = self
+            .lease_lock
+            .acquire(&self.holder_id, acquire_timeout)
+            .await;
+        if let Err(e) = self.withdraw().await {
+            log::warn!(
+                "{}.commit({}) => withdraw intent: {}",
+                self.lease_lock.client().lease_name(),
+                &self.holder_id,
+                e
+            );
+        }
+        acquired
+    }
+
+    /// Withdraw the intent without acquiring.
+    pub async fn cancel(self) -> Result<(), Error> {
+        self.withdraw().await
+    }
+
+    async fn withdraw(&self) -> Result<(), Error> {
+        let client = self.lease_lock.client();
+        // Leave an intent announced by somebody else in the meantime.
+        let lease_state = client.get_state().await?;
+        let identity = client.holder_identity(&self.holder_id);
+        if lease_state.pending_holder() == Some(identity.as_str()) {
+            client.annotate(PENDING_HOLDER_ANNOTATION, None).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use crate::lease::LeaseState;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Local record of the last lease held,
+/// see [LeaseLock::with_state_file](crate::LeaseLock::with_state_file).
+#[derive(Clone)]
+pub(crate) struct StateFile {
+    path: PathBuf,
+    pub(crate) cooldown: Duration,
+}
+
+pub(crate) struct Persisted {
+    pub(crate) lease_name: String,
+    pub(crate) holder: String,
+    pub(crate) transitions: i32,
+    pub(crate) held: bool,
+}
+
+impl StateFile {
+    pub(crate) fn new(path: PathBuf, cooldown: Duration) -> Self {
+        Self { path, cooldown }
+    }
+
+    /// Last record, or None if there is none or it can't be read.
+    pub(crate) fn load(&self) -> Option<Persisted> {
+        let json: serde_json::Value = match std::fs::read(&self.path) {
+            Ok(bytes) => match serde_json::from_slice(&bytes) {
+                Ok(json) => json,
+                Err(e) => {
+                    log::warn!("state file {:?} => {}; ignore", &self.path, e);
+                    return None;
+                }
+            },
+            Err(e) => {
+                log::debug!("state file {:?} => {}", &self.path, e);
+                return None;
+            }
+        };
+        Some(Persisted {
+            lease_name: json["leaseName"].as_str()?.to_string(),
+            holder: json["holder"].as_str()?.to_string(),
+            transitions: json["transitions"].as_i64()? as i32,
+            held: json["held"].as_bool()?,
+        })
+    }
+
+    /// Record `lease_state` as held or released. Best-effort: failures are logged.
+    pub(crate) fn store(&self, lease_state: &LeaseState, held: bool) {
+        let json = serde_json::json!({
+            "leaseName": lease_state.lease_name(),
+            "holder": lease_state.holder(),
+            "transitions": lease_state.transitions(),
+            "held": held,
+        });
+        // write a temporary file and rename it over the state file, so that a crash midway
+        // leaves the previous record rather than a truncated one
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        let written = std::fs::write(&tmp_path, json.to_string())
+            .and_then(|_| std::fs::rename(&tmp_path, &self.path));
+        if let Err(e) = written {
+            log::error!("state file {:?} => {}", &self.path, e);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Mutex;
+
+/// Source of randomness used by the crate, e.g. for backoff jitter.
+/// Inject a seeded one to make tests and simulations reproducible.
+pub trait RandomSource: Send + Sync {
+    /// Uniformly distributed value in `0..=max`.
+    fn gen_range(&self, max: u64) -> u64;
+}
+
+/// [RandomSource] backed by the thread-local generator of `rand`.
+pub struct ThreadRandom;
+
+impl RandomSource for ThreadRandom {
+    fn gen_range(&self, max: u64) -> u64 {
+        rand::thread_rng().gen_range(0..=max)
+    }
+}
+
+/// Deterministic [RandomSource] producing the same sequence for the same seed.
+pub struct SeededRandom(Mutex<StdRng>);
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> Self {
+        Self(Mutex::new(StdRng::seed_from_u64(seed)))
+    }
+}
+
+impl RandomSource for SeededRandom {
+    fn gen_range(&self, max: u64) -> u64 {
+        self.0.lock().unwrap().gen_range(0..=max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::*;
+
+    #[test]
+    fn seeded_is_reproducible() {
+        let (a, b) = (SeededRandom::new(42), SeededRandom::new(42));
+        for max in 1..100 {
+            assert_eq!(a.gen_range(max), b.gen_range(max));
+        }
+    }
+}
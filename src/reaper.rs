@@ -0,0 +1,157 @@
+use crate::lease::{Error, LeaseLock, LeaseState};
+use http::StatusCode;
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use k8s_openapi::api::core::v1::Event;
+use kube::api::{ListParams, PostParams};
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// Reason of the events emitted for reaped leases.
+const REAPED_REASON: &str = "ExpiredHolderReaped";
+
+/// Component the events are reported by.
+const REPORTING_COMPONENT: &str = "rust-kube-lease";
+
+/// Janitor clearing the holder of leases expired for longer than a grace period, so that
+/// dashboards don't show dead processes as holders for hours. Each reaped lease gets a
+/// Kubernetes Event naming the dead holder. Any replica may run it: the holder is only cleared
+/// if the lease is unchanged since listed.
+pub struct Reaper {
+    api: kube::Api<LeaseObject>,
+    events: kube::Api<Event>,
+    namespace: String,
+    grace: Duration,
+    interval: Duration,
+    label_selector: Option<String>,
+}
+
+impl Reaper {
+    /// Reaper of the leases in `namespace`.
+    pub fn new(client: kube::Client, namespace: &str) -> Self {
+        Self {
+            api: kube::Api::namespaced(client.clone(), namespace),
+            events: kube::Api::namespaced(client, namespace),
+            namespace: namespace.to_string(),
+            grace: Duration::from_secs(300),
+            interval: Duration::from_secs(60),
+            label_selector: None,
+        }
+    }
+
+    /// Reap leases expired for longer than `grace`. Default is 5 minutes.
+    pub fn with_grace(mut self, grace: Duration) -> Self {
+        self.grace = grace;
+        self
+    }
+
+    /// Wait between passes of [Reaper::run]. Default is 1 minute.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Only reap leases matching `selector`, e.g. `app=my-operator`.
+    pub fn with_label_selector(mut self, selector: impl Into<String>) -> Self {
+        self.label_selector = Some(selector.into());
+        self
+    }
+
+    /// Reap every `interval` forever. Drop the future to stop.
+    pub async fn run(&self) {
+        loop {
+            if let Err(e) = self.reap_once().await {
+                log::error!("{}.reap() => {}", &self.namespace, e);
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Single pass over the leases: clear the holder of each lease expired for longer than the
+    /// grace period. Return the released states. Failures to release a lease are logged and
+    /// leave it to the next pass.
+    pub async fn reap_once(&self) -> Result<Vec<LeaseState>, Error> {
+        let mut list_params = ListParams::default();
+        if let Some(selector) = &self.label_selector {
+            list_params = list_params.labels(selector);
+        }
+        let grace = chrono::Duration::from_std(self.grace).unwrap_or(chrono::Duration::MAX);
+        let mut reaped = Vec::new();
+        for lease_obj in self.api.list(&list_params).await? {
+            let uid = lease_obj.metadata.uid.clone();
+            let lease_state = LeaseState::try_from(lease_obj)?;
+            let dead_holder = match lease_state.holder() {
+                Some(holder) if lease_state.expires_at() + grace <= chrono::Utc::now() => {
+                    holder.to_string()
+                }
+                _ => continue,
+            };
+            log::warn!(
+                "{}.reap() => holder {} expired at {}; release",
+                lease_state.lease_name(),
+                &dead_holder,
+                lease_state.expires_at()
+            );
+            let lease_lock = LeaseLock::new(self.api.clone(), lease_state.lease_name().to_string());
+            // conditional on the listed resourceVersion, so that a holder renewing meanwhile
+            // is not evicted
+            let released = match lease_lock.client().release_patch(&lease_state, None).await {
+                Ok(released) => released,
+                Err(Error::Kube(kube::Error::Api(api_err)))
+                    if api_err.code == StatusCode::CONFLICT =>
+                {
+                    log::debug!(
+                        "{}.reap() => changed since listed; skip",
+                        lease_state.lease_name()
+                    );
+                    continue;
+                }
+                Err(e) => {
+                    log::error!("{}.reap() => {}", lease_state.lease_name(), e);
+                    continue;
+                }
+            };
+            self.emit(&lease_state, &dead_holder, uid).await;
+            reaped.push(released);
+        }
+        Ok(reaped)
+    }
+
+    async fn emit(&self, lease_state: &LeaseState, dead_holder: &str, uid: Option<String>) {
+        let now = chrono::Utc::now();
+        let event: Event = match serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "generateName": format!("{}.", lease_state.lease_name()),
+                "namespace": &self.namespace,
+            },
+            "involvedObject": {
+                "apiVersion": "coordination.k8s.io/v1",
+                "kind": "Lease",
+                "name": lease_state.lease_name(),
+                "namespace": &self.namespace,
+                "uid": uid,
+            },
+            "type": "Warning",
+            "reason": REAPED_REASON,
+            "message": format!(
+                "holder {} expired at {}; holder cleared",
+                dead_holder,
+                lease_state.expires_at().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            ),
+            "source": { "component": REPORTING_COMPONENT },
+            "reportingComponent": REPORTING_COMPONENT,
+            "reportingInstance": "",
+            "firstTimestamp": now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "lastTimestamp": now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            "count": 1,
+        })) {
+            Ok(event) => event,
+            Err(e) => {
+                log::error!("{}.emit() => {}", lease_state.lease_name(), e);
+                return;
+            }
+        };
+        if let Err(e) = self.events.create(&PostParams::default(), &event).await {
+            log::error!("{}.emit() => {}", lease_state.lease_name(), e);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Process-wide registry of held leases, so that a process can tell which distributed locks
+//! it holds right now, e.g. from a health endpoint.
+
+use crate::lease::UtcInstant;
+use once_cell::sync::Lazy;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+static HELD: Lazy<Mutex<BTreeMap<u64, HeldInfo>>> = Lazy::new(Default::default);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A lease held by a live guard of this process.
+#[derive(Clone, Debug)]
+pub struct HeldInfo {
+    pub lease_name: String,
+    /// Identity written to the lease.
+    pub holder: String,
+    /// Acquire time written to the lease.
+    pub acquire_time: Option<UtcInstant>,
+    /// Local time the guard was created.
+    pub held_since: Instant,
+    /// See [LeaseGuard::correlation_id](crate::LeaseGuard::correlation_id).
+    pub correlation_id: String,
+}
+
+/// Entry of a guard in the registry, removed by [Registration::remove].
+pub(crate) struct Registration(u64);
+
+impl Registration {
+    pub(crate) fn remove(&self) {
+        HELD.lock().unwrap().remove(&self.0);
+    }
+}
+
+pub(crate) fn register(info: HeldInfo) -> Registration {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    HELD.lock().unwrap().insert(id, info);
+    Registration(id)
+}
+
+/// Leases currently held by this process, in acquisition order. A guard leaves the list once
+/// dropped or once its renewal found the lease lost.
+pub fn held_leases() -> Vec<HeldInfo> {
+    HELD.lock().unwrap().values().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::*;
+
+    #[test]
+    fn register_and_remove() {
+        let registration = register(HeldInfo {
+            lease_name: "registry-test".into(),
+            holder: "a".into(),
+            acquire_time: None,
+            held_since: Instant::now(),
+            correlation_id: "0".into(),
+        });
+        let held = |name: &str| held_leases().iter().any(|info| info.lease_name == name);
+        assert!(held("registry-test"));
+        registration.remove();
+        assert!(!held("registry-test"));
+    }
+}
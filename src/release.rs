@@ -0,0 +1,16 @@
+use crate::lease::{Error, LeaseGuard, LeaseState};
+use futures::stream::{self, StreamExt};
+
+/// Release `guards` inline, up to `concurrency` at a time, e.g. on shutdown of a process
+/// holding many per-item leases. Return the result of each release, in the order of `guards`;
+/// see [LeaseGuard::release].
+pub async fn release_all(
+    guards: impl IntoIterator<Item = LeaseGuard>,
+    concurrency: usize,
+) -> Vec<Result<LeaseState, Error>> {
+    stream::iter(guards)
+        .map(LeaseGuard::release)
+        .buffered(concurrency.max(1))
+        .collect()
+        .await
+}
@@ -0,0 +1,49 @@
+use crate::lease::{Error, LeaseGuard, LeaseLock, LeaseState};
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use std::ops::Deref;
+
+/// Renewal of a lease acquired by other tooling, e.g. a Go init container, so that acquiring
+/// and holding can happen in different components. Dereferences to the [LeaseGuard] of the
+/// adopted lease, for [LeaseGuard::lost], [LeaseGuard::renewals] and the like; the lease is
+/// released when dropped.
+pub struct Renewer {
+    lease_lock: LeaseLock,
+    guard: LeaseGuard,
+}
+
+impl Renewer {
+    /// Adopt lease `lease_name` held by `holder`, as written to the lease.
+    /// Return [Error::Lost] if it isn't held by `holder`.
+    pub async fn adopt(
+        api: kube::Api<LeaseObject>,
+        lease_name: &str,
+        holder: &str,
+    ) -> Result<Self, Error> {
+        Self::adopt_with(LeaseLock::new(api, lease_name.to_string()), holder).await
+    }
+
+    /// Adopt the lease of `lease_lock` like [Renewer::adopt]. Renewals write the lease duration
+    /// configured on `lease_lock`, which should match the one of the acquiring tooling.
+    pub async fn adopt_with(lease_lock: LeaseLock, holder: &str) -> Result<Self, Error> {
+        let guard = lease_lock.adopt(holder).await?.ok_or(Error::Lost)?;
+        log::info!("{}.adopt({}) => renewing", lease_lock.name(), holder);
+        Ok(Self { lease_lock, guard })
+    }
+
+    pub fn lease_lock(&self) -> &LeaseLock {
+        &self.lease_lock
+    }
+
+    /// Release the lease, see [LeaseGuard::release].
+    pub async fn release(self) -> Result<LeaseState, Error> {
+        self.guard.release().await
+    }
+}
+
+impl Deref for Renewer {
+    type Target = LeaseGuard;
+
+    fn deref(&self) -> &LeaseGuard {
+        &self.guard
+    }
+}
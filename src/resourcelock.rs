@@ -0,0 +1,336 @@
+use crate::consts::LEADER_ANNOTATION;
+use crate::lease::{Error, UtcInstant};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use http::StatusCode;
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use k8s_openapi::api::core::v1::{ConfigMap, Endpoints};
+use kube::api::{Patch, PatchParams, PostParams};
+use kube::Resource;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// Leader election record in client-go's format, as stored by a [ResourceLock].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeaderElectionRecord {
+    #[serde(default)]
+    pub holder_identity: String,
+    #[serde(default)]
+    pub lease_duration_seconds: i32,
+    pub acquire_time: Option<UtcInstant>,
+    pub renew_time: Option<UtcInstant>,
+    #[serde(default)]
+    pub leader_transitions: i32,
+}
+
+/// Storage of a [LeaderElectionRecord] in some Kubernetes object, decoupling the election from
+/// the Lease API: e.g. for migrating from operators that elect through ConfigMaps or Endpoints,
+/// or for clusters where Lease RBAC is restricted. See [try_acquire_or_renew].
+///
+/// The locking algorithm of [LeaseLock](crate::LeaseLock) is not decoupled from the Lease API:
+/// it does not run on this trait, since its guards, renewal and options rely on server-side apply
+/// of Lease objects. Only [try_acquire_or_renew] runs on it, whose callers drive renewal
+/// themselves, by calling it again well within the lease duration, and step down by not doing so.
+pub trait ResourceLock: Send + Sync {
+    /// Current record and the object's resourceVersion, or None if the object is missing.
+    fn get(&self) -> BoxFuture<'_, Result<Option<(LeaderElectionRecord, String)>, Error>>;
+
+    /// Create the object with `record`.
+    fn create<'a>(&'a self, record: &'a LeaderElectionRecord) -> BoxFuture<'a, Result<(), Error>>;
+
+    /// Replace the record, failing with a conflict unless the object is still at `version`.
+    fn update<'a>(
+        &'a self,
+        record: &'a LeaderElectionRecord,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<(), Error>>;
+
+    /// Kind and name of the object, for logs.
+    fn describe(&self) -> String;
+}
+
+/// One round of client-go's election on `lock`: acquire the record for `identity` if it is free
+/// or expired, renew it if `identity` holds it already. Return whether `identity` holds it now.
+pub async fn try_acquire_or_renew(
+    lock: &dyn ResourceLock,
+    identity: &str,
+    lease_duration: Duration,
+) -> Result<bool, Error> {
+    try_acquire_or_renew_at(lock, identity, lease_duration, chrono::Utc::now()).await
+}
+
+/// [try_acquire_or_renew] as of `now` rather than the system clock, e.g. for simulations on a
+/// virtual clock. On success, `identity` holds the record until `now` plus `lease_duration`,
+/// truncated to whole seconds as stored in the record.
+pub async fn try_acquire_or_renew_at(
+    lock: &dyn ResourceLock,
+    identity: &str,
+    lease_duration: Duration,
+    now: UtcInstant,
+) -> Result<bool, Error> {
+    let lease_duration_seconds = i32::try_from(lease_duration.as_secs())?;
+    let (current, version) = match lock.get().await? {
+        Some(found) => found,
+        None => {
+            let record = LeaderElectionRecord {
+                holder_identity: identity.to_string(),
+                lease_duration_seconds,
+                acquire_time: Some(now),
+                renew_time: Some(now),
+                leader_transitions: 0,
+            };
+            return match lock.create(&record).await {
+                Ok(()) => Ok(true),
+                Err(e) if conflict(&e) => Ok(false),
+                Err(e) => Err(e),
+            };
+        }
+    };
+    let held_by_us = current.holder_identity == identity;
+    let expires_at = current.renew_time.map(|renew_time| {
+        renew_time + chrono::Duration::seconds(current.lease_duration_seconds.into())
+    });
+    if !held_by_us && !current.holder_identity.is_empty() && expires_at > Some(now) {
+        log::debug!(
+            "{}.try_acquire_or_renew({}) => held by {}",
+            lock.describe(),
+            identity,
+            &current.holder_identity
+        );
+        return Ok(false);
+    }
+    let record = LeaderElectionRecord {
+        holder_identity: identity.to_string(),
+        lease_duration_seconds,
+        acquire_time: if held_by_us {
+            current.acquire_time
+        } else {
+            Some(now)
+        },
+        renew_time: Some(now),
+        leader_transitions: if held_by_us {
+            current.leader_transitions
+        } else {
+            current.leader_transitions + 1
+        },
+    };
+    match lock.update(&record, &version).await {
+        Ok(()) => Ok(true),
+        Err(e) if conflict(&e) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+fn conflict(error: &Error) -> bool {
+    error.status_code() == Some(StatusCode::CONFLICT)
+}
+
+/// [ResourceLock] storing the record in the `control-plane.alpha.kubernetes.io/leader`
+/// annotation of an object, as client-go's ConfigMap and Endpoints locks do.
+pub struct AnnotationLock<K> {
+    api: kube::Api<K>,
+    name: String,
+}
+
+/// client-go's `configmaps` lock.
+pub type ConfigMapLock = AnnotationLock<ConfigMap>;
+
+/// client-go's `endpoints` lock.
+pub type EndpointsLock = AnnotationLock<Endpoints>;
+
+impl<K> AnnotationLock<K> {
+    pub fn new(api: kube::Api<K>, name: impl Into<String>) -> Self {
+        Self {
+            api,
+            name: name.into(),
+        }
+    }
+}
+
+impl<K> ResourceLock for AnnotationLock<K>
+where
+    K: Resource<DynamicType = ()> + Clone + DeserializeOwned + Serialize + Debug + Send + Sync,
+{
+    fn get(&self) -> BoxFuture<'_, Result<Option<(LeaderElectionRecord, String)>, Error>> {
+        async move {
+            let obj = match self.api.get(&self.name).await {
+                Ok(obj) => obj,
+                Err(kube::Error::Api(api_err)) if api_err.code == StatusCode::NOT_FOUND => {
+                    return Ok(None)
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let record = match obj
+                .meta()
+                .annotations
+                .as_ref()
+                .and_then(|a| a.get(LEADER_ANNOTATION))
+            {
+                Some(record) => serde_json::from_str(record)?,
+                None => LeaderElectionRecord::default(),
+            };
+            let version = obj.meta().resource_version.clone().unwrap_or_default();
+            Ok(Some((record, version)))
+        }
+        .boxed()
+    }
+
+    fn create<'a>(&'a self, record: &'a LeaderElectionRecord) -> BoxFuture<'a, Result<(), Error>> {
+        async move {
+            let obj: K = serde_json::from_value(serde_json::json!({
+                "apiVersion": K::api_version(&()),
+                "kind": K::kind(&()),
+                "metadata": {
+                    "name": &self.name,
+                    "annotations": { LEADER_ANNOTATION: serde_json::to_string(record)? },
+                },
+            }))?;
+            self.api.create(&PostParams::default(), &obj).await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn update<'a>(
+        &'a self,
+        record: &'a LeaderElectionRecord,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        async move {
+            // resourceVersion in a patch is a precondition: it conflicts if the object changed
+            let patch = serde_json::json!({
+                "metadata": {
+                    "resourceVersion": version,
+                    "annotations": { LEADER_ANNOTATION: serde_json::to_string(record)? },
+                },
+            });
+            self.api
+                .patch(&self.name, &PatchParams::default(), &Patch::Merge(&patch))
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn describe(&self) -> String {
+        format!("{}/{}", K::kind(&()), &self.name)
+    }
+}
+
+/// [ResourceLock] storing the record in the spec of a coordination.k8s.io Lease, as client-go's
+/// `leases` lock does. [LeaseLock](crate::LeaseLock) builds on Leases directly; this lock is
+/// for elections shared with client-go processes through [try_acquire_or_renew].
+pub struct LeaseResourceLock {
+    api: kube::Api<LeaseObject>,
+    name: String,
+}
+
+impl LeaseResourceLock {
+    pub fn new(api: kube::Api<LeaseObject>, name: impl Into<String>) -> Self {
+        Self {
+            api,
+            name: name.into(),
+        }
+    }
+
+    fn spec(record: &LeaderElectionRecord) -> serde_json::Value {
+        // MicroTime fields want microsecond precision
+        let micro_time = |t: Option<UtcInstant>| {
+            t.map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Micros, false))
+        };
+        serde_json::json!({
+            "holderIdentity": &record.holder_identity,
+            "leaseDurationSeconds": record.lease_duration_seconds,
+            "acquireTime": micro_time(record.acquire_time),
+            "renewTime": micro_time(record.renew_time),
+            "leaseTransitions": record.leader_transitions,
+        })
+    }
+}
+
+impl ResourceLock for LeaseResourceLock {
+    fn get(&self) -> BoxFuture<'_, Result<Option<(LeaderElectionRecord, String)>, Error>> {
+        async move {
+            let lease = match self.api.get(&self.name).await {
+                Ok(lease) => lease,
+                Err(kube::Error::Api(api_err)) if api_err.code == StatusCode::NOT_FOUND => {
+                    return Ok(None)
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let spec = lease.spec.unwrap_or_default();
+            let record = LeaderElectionRecord {
+                holder_identity: spec.holder_identity.unwrap_or_default(),
+                lease_duration_seconds: spec.lease_duration_seconds.unwrap_or_default(),
+                acquire_time: spec.acquire_time.map(|t| t.0),
+                renew_time: spec.renew_time.map(|t| t.0),
+                leader_transitions: spec.lease_transitions.unwrap_or_default(),
+            };
+            Ok(Some((
+                record,
+                lease.metadata.resource_version.unwrap_or_default(),
+            )))
+        }
+        .boxed()
+    }
+
+    fn create<'a>(&'a self, record: &'a LeaderElectionRecord) -> BoxFuture<'a, Result<(), Error>> {
+        async move {
+            let lease: LeaseObject = serde_json::from_value(serde_json::json!({
+                "apiVersion": "coordination.k8s.io/v1",
+                "kind": "Lease",
+                "metadata": { "name": &self.name },
+                "spec": Self::spec(record),
+            }))?;
+            self.api.create(&PostParams::default(), &lease).await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn update<'a>(
+        &'a self,
+        record: &'a LeaderElectionRecord,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        async move {
+            let patch = serde_json::json!({
+                "metadata": { "resourceVersion": version },
+                "spec": Self::spec(record),
+            });
+            self.api
+                .patch(&self.name, &PatchParams::default(), &Patch::Merge(&patch))
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn describe(&self) -> String {
+        format!("Lease/{}", &self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::resourcelock::*;
+
+    #[test]
+    fn client_go_record() {
+        let json = concat!(
+            r#"{"holderIdentity":"pod-a","leaseDurationSeconds":15,"#,
+            r#""acquireTime":"2021-06-01T10:00:00Z","renewTime":"2021-06-01T10:00:05Z","#,
+            r#""leaderTransitions":2}"#
+        );
+        let record: LeaderElectionRecord = serde_json::from_str(json).unwrap();
+        assert_eq!(record.holder_identity, "pod-a");
+        assert_eq!(record.lease_duration_seconds, 15);
+        assert_eq!(record.leader_transitions, 2);
+        let parsed: LeaderElectionRecord =
+            serde_json::from_str(&serde_json::to_string(&record).unwrap()).unwrap();
+        assert_eq!(parsed, record);
+    }
+}
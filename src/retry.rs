@@ -0,0 +1,37 @@
+use crate::lease::Error;
+use std::time::Duration;
+
+/// Lease operation that failed, see [RetryPolicy].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// Reading or writing the lease while acquiring it.
+    Acquire,
+    /// Background renewal of a held lease.
+    Renew,
+    /// Release of the lease after its guard is dropped.
+    Release,
+}
+
+/// Verdict of a [RetryPolicy].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Retry the operation after the given delay.
+    Retry(Duration),
+    /// Give up: acquire returns the error, renewal stops as if ownership was lost,
+    /// release leaves the lease to expire.
+    Fail,
+}
+
+/// Central retry semantics, consulted on every error of a lease operation.
+/// Without a policy, acquire fails on the first error, renewal retries on its next tick
+/// and release gives up.
+pub trait RetryPolicy: Send + Sync {
+    /// Decide on the `attempt`th consecutive failure of `operation`, `elapsed` after the first.
+    fn on_error(
+        &self,
+        operation: Operation,
+        error: &Error,
+        attempt: u32,
+        elapsed: Duration,
+    ) -> RetryDecision;
+}
@@ -0,0 +1,66 @@
+use crate::lease::{LeaseState, UtcInstant};
+use serde::{Deserialize, Serialize};
+
+/// Role of a process in the election for a lease, for application state machines.
+/// Serializes with a `role` tag, e.g. `{"role":"leader","term":3,"since":"..."}`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "role", rename_all = "camelCase")]
+pub enum Role {
+    /// Holds the lease in term `term` (its `leaseTransitions`), acquired at `since`.
+    Leader { term: i32, since: UtcInstant },
+    /// Another process holds the lease.
+    Follower { leader: String },
+    /// Contends for the lease, which has no live holder.
+    Candidate,
+    /// Can't take part, e.g. because the API server is unreachable.
+    Offline,
+    /// Taken out of the election on purpose,
+    /// see [LeaderElector::set_enabled](crate::LeaderElector::set_enabled).
+    Standby,
+}
+
+impl Role {
+    /// Role of `identity`, as written to the lease, by `lease_state`.
+    pub fn of(lease_state: &LeaseState, identity: &str) -> Role {
+        match lease_state.owner() {
+            Some(owner) if owner == identity => Role::Leader {
+                term: lease_state.transitions(),
+                since: lease_state
+                    .acquire_time()
+                    .unwrap_or_else(|| lease_state.renew_time()),
+            },
+            Some(owner) => Role::Follower {
+                leader: owner.to_string(),
+            },
+            None => Role::Candidate,
+        }
+    }
+
+    /// Name of the role, e.g. for metrics labels.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Role::Leader { .. } => "leader",
+            Role::Follower { .. } => "follower",
+            Role::Candidate => "candidate",
+            Role::Offline => "offline",
+            Role::Standby => "standby",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::role::*;
+
+    #[test]
+    fn serde() {
+        let role = Role::Follower { leader: "a".into() };
+        let json = serde_json::to_value(&role).unwrap();
+        assert_eq!(json, serde_json::json!({"role": "follower", "leader": "a"}));
+        assert_eq!(serde_json::from_value::<Role>(json).unwrap(), role);
+        assert_eq!(
+            serde_json::to_value(Role::Offline).unwrap(),
+            serde_json::json!({"role": "offline"})
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use crate::consts::SCHEMA_VERSION_ANNOTATION;
+use std::collections::BTreeMap;
+
+/// Version of the annotation schema this crate reads and writes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+pub(crate) type Annotations = BTreeMap<String, String>;
+
+/// Conversion of crate annotations between schema versions `from` and `from + 1`.
+#[allow(dead_code)] // not constructed until the schema changes for the first time
+struct Migration {
+    from: u32,
+    upgrade: fn(&mut Annotations),
+    downgrade: fn(&mut Annotations),
+}
+
+/// Migrations between consecutive schema versions, in ascending order.
+/// Each schema change adds an entry here, so that mixed-version fleets keep understanding
+/// each other during rollouts.
+const MIGRATIONS: &[Migration] = &[];
+
+/// Bring annotations read from a lease to [SCHEMA_VERSION]. Return the version they were
+/// written in; leases without the version annotation predate versioning and count as 1.
+pub(crate) fn upgrade(annotations: &mut Annotations) -> u32 {
+    let version = annotations
+        .get(SCHEMA_VERSION_ANNOTATION)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1);
+    if version > SCHEMA_VERSION {
+        log::warn!(
+            "lease annotations use schema {} newer than {}; reading best-effort",
+            version,
+            SCHEMA_VERSION
+        );
+    }
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= version) {
+        (migration.upgrade)(annotations);
+    }
+    version
+}
+
+/// Convert annotations in [SCHEMA_VERSION] to the older version `to`, and stamp the version.
+pub(crate) fn downgrade(annotations: &mut Annotations, to: u32) {
+    for migration in MIGRATIONS.iter().rev().filter(|m| m.from >= to) {
+        (migration.downgrade)(annotations);
+    }
+    annotations.insert(SCHEMA_VERSION_ANNOTATION.into(), to.to_string());
+}
@@ -0,0 +1,71 @@
+use crate::lease::LeaseLock;
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use sha2::{Digest, Sha256};
+
+/// Maps an arbitrary key space onto `shards` leases named `<prefix>-<shard>` by consistent
+/// hashing, a building block for partitioned consumers: whoever holds a shard's lease owns
+/// its keys. When the shard count changes, only about `1/shards` of the keys move.
+pub struct ShardedLocks {
+    locks: Vec<LeaseLock>,
+}
+
+impl ShardedLocks {
+    pub fn new(api: kube::Api<LeaseObject>, prefix: &str, shards: u32) -> Self {
+        Self {
+            locks: (0..shards.max(1))
+                .map(|shard| LeaseLock::new(api.clone(), format!("{}-{}", prefix, shard)))
+                .collect(),
+        }
+    }
+
+    /// Shard owning `key`.
+    pub fn shard_of(&self, key: &str) -> usize {
+        let digest = Sha256::digest(key.as_bytes());
+        let mut hash = [0; 8];
+        hash.copy_from_slice(&digest[..8]);
+        jump_hash(u64::from_be_bytes(hash), self.locks.len())
+    }
+
+    /// Lock of the shard owning `key`.
+    pub fn lock_for(&self, key: &str) -> &LeaseLock {
+        &self.locks[self.shard_of(key)]
+    }
+
+    /// Locks of all shards, indexed by shard.
+    pub fn locks(&self) -> &[LeaseLock] {
+        &self.locks
+    }
+
+    /// Wait for inflight operations on the locks of all shards to complete, e.g. on shutdown,
+    /// see [LeaseLock::complete_all_operations].
+    pub async fn complete_all_operations(&self) {
+        for lock in &self.locks {
+            lock.complete_all_operations().await;
+        }
+    }
+}
+
+/// Jump consistent hash (Lamping, Veach): bucket of `key` among `buckets`.
+fn jump_hash(mut key: u64, buckets: usize) -> usize {
+    let (mut b, mut j) = (-1i64, 0i64);
+    while j < buckets as i64 {
+        b = j;
+        key = key.wrapping_mul(2862933555777941757).wrapping_add(1);
+        j = ((b + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    b as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sharded::*;
+
+    #[test]
+    fn jump_hash_moves_few_keys() {
+        let moved = (0..10_000u64)
+            .filter(|&key| jump_hash(key, 10) != jump_hash(key, 11))
+            .count();
+        assert!(moved < 1_500, "{} keys moved", moved);
+        assert!((0..10_000u64).all(|key| jump_hash(key, 1) == 0));
+    }
+}
@@ -0,0 +1,311 @@
+use crate::consts::CO_HOLDERS_ANNOTATION;
+use crate::defaults;
+use crate::lease::{self, Error};
+use crate::task::{spawn_named, Completion};
+use futures::future::{AbortHandle, Abortable};
+use http::StatusCode;
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use kube::api::{Patch, PatchParams};
+use kube::ResourceExt;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::Sender;
+use tokio::sync::watch;
+use tokio_retry::strategy::ExponentialBackoff;
+
+type Api = kube::Api<LeaseObject>;
+type Members = BTreeMap<String, chrono::DateTime<chrono::Utc>>;
+
+/// Lock held by up to `capacity` co-holders at once (e.g. an active-active pair), each renewing
+/// independently. Membership is kept in an annotation of the lease and updated with
+/// compare-and-swap, so the bound is enforced across processes.
+///
+/// The lease must not be used with [LeaseLock](crate::LeaseLock) at the same time.
+#[derive(Clone)]
+pub struct SharedLeaseLock {
+    client: SharedLeaseClient,
+    completion: Completion,
+}
+
+#[derive(Clone)]
+struct SharedLeaseClient {
+    api: Api,
+    lease_name: String,
+    capacity: usize,
+    lease_duration: Duration,
+    renew_interval: Option<Duration>,
+    expo: ExponentialBackoff,
+    field_manager: String,
+}
+
+/// RAII membership in a [SharedLeaseLock]. When dropped, schedules leaving the lease.
+pub struct SharedLeaseGuard {
+    client: SharedLeaseClient,
+    holder_id: String,
+    abort_handle: AbortHandle,
+    completion_tx: Sender<()>,
+    lost_rx: watch::Receiver<bool>,
+}
+
+impl SharedLeaseGuard {
+    /// Whether the membership is still held as far as this process knows: false once renewal
+    /// found it gone, e.g. expired while renewals were failing.
+    pub fn is_held(&self) -> bool {
+        !*self.lost_rx.borrow()
+    }
+
+    /// Resolve once the membership is lost, see [SharedLeaseGuard::is_held].
+    pub async fn lost(&self) {
+        let mut lost_rx = self.lost_rx.clone();
+        while !*lost_rx.borrow_and_update() {
+            if lost_rx.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for SharedLeaseGuard {
+    fn drop(&mut self) {
+        log::debug!("{}.drop({})", &self.client.lease_name, &self.holder_id);
+        self.abort_handle.abort();
+        let task_name = format!("lease-leave:{}", &self.client.lease_name);
+        spawn_named(&task_name, None, {
+            let client = self.client.clone();
+            let holder_id = self.holder_id.clone();
+            let completion_tx = self.completion_tx.clone();
+            async move {
+                let leave = client.update(|members| members.remove(&holder_id).is_some());
+                if let Err(e) = leave.await {
+                    log::error!("{}.leave({}) => {}", &client.lease_name, &holder_id, e);
+                }
+                drop(completion_tx);
+            }
+        });
+    }
+}
+
+impl SharedLeaseLock {
+    /// Lock `lease_name` shared by up to `capacity` co-holders. Panics if `capacity` is 0.
+    pub fn new(api: Api, lease_name: String, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity of shared lease {} is 0", lease_name);
+        let defaults = defaults::current();
+        Self {
+            client: SharedLeaseClient {
+                api,
+                lease_name,
+                capacity,
+                lease_duration: defaults.lease_duration,
+                renew_interval: None,
+                expo: defaults.expo,
+                field_manager: defaults.field_manager,
+            },
+            completion: Completion::new(),
+        }
+    }
+
+    /// Configure expiry time of each membership. Default is 10 seconds, unless changed by
+    /// [crate::defaults].
+    pub fn with_lease_duration(mut self, duration: Duration) -> Self {
+        self.client.lease_duration = duration;
+        self
+    }
+
+    /// Renew each membership every `interval`. Default is 2/5 of the lease duration, as for
+    /// [LeaseLock::with_renew_interval](crate::LeaseLock::with_renew_interval).
+    pub fn with_renew_interval(mut self, interval: Duration) -> Self {
+        self.client.renew_interval = Some(interval);
+        self
+    }
+
+    /// Customize backoff policy. Default is
+    /// `ExponentialBackoff::from_millis(10).max_delay(Duration::from_secs(1))`,
+    /// unless changed by [crate::defaults].
+    pub fn with_expo_backoff(mut self, expo: ExponentialBackoff) -> Self {
+        self.client.expo = expo;
+        self
+    }
+
+    /// Wait for all inflight operations on this lock to complete.
+    pub async fn complete_all_operations(&self) {
+        self.completion.complete_all().await;
+    }
+
+    /// Current live co-holders.
+    pub async fn holders(&self) -> Result<Vec<String>, Error> {
+        let (members, _) = self.client.get_members().await?;
+        Ok(members.into_keys().collect())
+    }
+
+    /// Join the co-holders; wait while all `capacity` seats are taken.
+    /// Return [Error::AcquireTimeout] if no seat freed up within `acquire_timeout`, or
+    /// [Error::Config] if the lease duration or renew interval are invalid.
+    pub async fn acquire(
+        &self,
+        holder_id: &str,
+        acquire_timeout: Option<Duration>,
+    ) -> Result<SharedLeaseGuard, Error> {
+        log::debug!(
+            "{}.acquire({}, {:?})",
+            &self.client.lease_name,
+            holder_id,
+            acquire_timeout
+        );
+        lease::validate_renewal(self.client.lease_duration, self.client.renew_interval)?;
+        let deadline = acquire_timeout.map(|to| Instant::now() + to);
+        let capacity = self.client.capacity;
+        let mut expo = self.client.expo.clone();
+        loop {
+            let joined = self
+                .client
+                .update(|members| {
+                    if members.contains_key(holder_id) || members.len() < capacity {
+                        members.insert(holder_id.to_string(), chrono::Utc::now());
+                        true
+                    } else {
+                        false
+                    }
+                })
+                .await?;
+            if joined {
+                let (lost_tx, lost_rx) = watch::channel(false);
+                return Ok(SharedLeaseGuard {
+                    client: self.client.clone(),
+                    holder_id: holder_id.to_string(),
+                    abort_handle: self
+                        .client
+                        .clone()
+                        .schedule_renewal(holder_id.to_string(), lost_tx),
+                    completion_tx: self.completion.token(),
+                    lost_rx,
+                });
+            }
+            let backoff = expo.next().expect("infinite backoff");
+            if let Some(d) = deadline {
+                if Instant::now() + backoff >= d {
+                    return Err(Error::acquire_timeout(d));
+                }
+            }
+            log::debug!(
+                "{}.acquire({}) => full:backoff({:?})",
+                &self.client.lease_name,
+                holder_id,
+                backoff
+            );
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// Join the co-holders if a seat is free right now. If not, return None.
+    pub async fn try_acquire(&self, holder_id: &str) -> Result<Option<SharedLeaseGuard>, Error> {
+        match self.acquire(holder_id, Some(Duration::ZERO)).await {
+            Ok(guard) => Ok(Some(guard)),
+            Err(Error::AcquireTimeout { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl SharedLeaseClient {
+    #[must_use]
+    fn schedule_renewal(self, holder_id: String, lost_tx: watch::Sender<bool>) -> AbortHandle {
+        let (abort_handle, abort_reg) = AbortHandle::new_pair();
+        let task_name = format!("lease-renew:{}", &self.lease_name);
+        spawn_named(
+            &task_name,
+            None,
+            Abortable::new(
+                async move {
+                    let interval = lease::renew_interval(self.lease_duration, self.renew_interval);
+                    let mut renewed = Instant::now();
+                    loop {
+                        tokio::time::sleep(interval).await;
+                        let renewal = self.update(|members| match members.get_mut(&holder_id) {
+                            Some(renew_time) => {
+                                *renew_time = chrono::Utc::now();
+                                true
+                            }
+                            None => false,
+                        });
+                        match renewal.await {
+                            Ok(true) => {
+                                renewed = Instant::now();
+                                continue;
+                            }
+                            Ok(false) => {}
+                            Err(e) => {
+                                log::error!("{}.renew({}) => {}", &self.lease_name, &holder_id, e);
+                                // others see the membership expired once the lease duration passed
+                                if renewed.elapsed() < self.lease_duration {
+                                    continue;
+                                }
+                            }
+                        }
+                        log::warn!(
+                            "{}: {} lost membership; stop renewal",
+                            &self.lease_name,
+                            &holder_id
+                        );
+                        let _ = lost_tx.send(true);
+                        return;
+                    }
+                },
+                abort_reg,
+            ),
+        );
+        abort_handle
+    }
+
+    /// Live members of the lease and the resourceVersion they were read at.
+    async fn get_members(&self) -> Result<(Members, String), Error> {
+        let lease_obj = self.api.get(&self.lease_name).await?;
+        let resource_version = lease_obj
+            .resource_version()
+            .ok_or_else(|| Error::Format("resourceVersion".into()))?;
+        let mut members: Members = match lease_obj.annotations().get(CO_HOLDERS_ANNOTATION) {
+            Some(json) => serde_json::from_str(json)?,
+            None => Members::new(),
+        };
+        let expiry = chrono::Duration::from_std(self.lease_duration)
+            .map_err(|_| Error::Config("lease duration out of range".into()))?;
+        let now = chrono::Utc::now();
+        members.retain(|_, renew_time| *renew_time + expiry > now);
+        Ok((members, resource_version))
+    }
+
+    /// Read-modify-write of live members: `f` edits them and tells whether to write back.
+    /// Retries on conflicting concurrent updates. Returns whether `f` asked to write.
+    async fn update(&self, f: impl Fn(&mut Members) -> bool) -> Result<bool, Error> {
+        let mut backoff = self.expo.clone();
+        loop {
+            let (mut members, resource_version) = self.get_members().await?;
+            if !f(&mut members) {
+                return Ok(false);
+            }
+            let patch = serde_json::json!({
+                "metadata": {
+                    "resourceVersion": resource_version,
+                    "annotations": {
+                        CO_HOLDERS_ANNOTATION: serde_json::to_string(&members)?,
+                    },
+                },
+            });
+            let params = PatchParams {
+                field_manager: Some(self.field_manager.clone()),
+                ..Default::default()
+            };
+            match self
+                .api
+                .patch(&self.lease_name, &params, &Patch::Merge(&patch))
+                .await
+            {
+                Ok(_) => return Ok(true),
+                Err(kube::Error::Api(api_err)) if api_err.code == StatusCode::CONFLICT => {
+                    log::debug!("{}.update() => conflict", &self.lease_name);
+                    tokio::time::sleep(backoff.next().expect("infinite backoff")).await;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
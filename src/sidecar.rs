@@ -0,0 +1,96 @@
+//! Lock operations served over a Unix socket, so that non-Rust processes in the same pod can use
+//! the crate's election logic through a sidecar. Enabled by the `sidecar` feature.
+//!
+//! The protocol is line-based; every request line gets one response line, `OK [<value>]` or
+//! `ERR <message>`:
+//!
+//! - `ACQUIRE <lease> <holder> [<timeout-ms>]` acquires the lease. It stays held until `RELEASE`
+//!   or until the connection is closed.
+//! - `RELEASE <lease>` releases a lease acquired on this connection.
+//! - `HOLDER <lease>` responds with the current holder of the lease, or `-` if there is none.
+
+use crate::lease::{LeaseGuard, LeaseLock};
+use crate::task::spawn_named;
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+type Api = kube::Api<LeaseObject>;
+
+/// Leases acquired on one connection.
+type Held = BTreeMap<String, (LeaseLock, LeaseGuard)>;
+
+/// Serve lock operations on leases of `api` on a Unix socket bound at `path`, until accepting
+/// a connection fails.
+pub async fn serve(api: Api, path: impl AsRef<Path>) -> std::io::Result<()> {
+    let listener = UnixListener::bind(path)?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        spawn_named("lease-sidecar", None, handle(api.clone(), stream));
+    }
+}
+
+async fn handle(api: Api, stream: UnixStream) {
+    let (read, mut write) = stream.into_split();
+    let mut lines = BufReader::new(read).lines();
+    let mut held = Held::new();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let response = match execute(&api, &mut held, &line).await {
+            Ok(None) => "OK\n".to_string(),
+            Ok(Some(value)) => format!("OK {}\n", value),
+            Err(e) => format!("ERR {}\n", e),
+        };
+        if write.write_all(response.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+    for (_, (lease_lock, guard)) in held {
+        drop(guard);
+        lease_lock.complete_all_operations().await;
+    }
+}
+
+async fn execute(api: &Api, held: &mut Held, line: &str) -> Result<Option<String>, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["ACQUIRE", lease_name, holder_id, timeout @ ..] => {
+            if held.contains_key(*lease_name) {
+                return Err("already held on this connection".into());
+            }
+            let acquire_timeout = match timeout {
+                [] => None,
+                [ms] => Some(Duration::from_millis(
+                    ms.parse().map_err(|_| "invalid timeout")?,
+                )),
+                _ => return Err("usage: ACQUIRE <lease> <holder> [<timeout-ms>]".into()),
+            };
+            let lease_lock = LeaseLock::new(api.clone(), lease_name.to_string());
+            let guard = lease_lock
+                .acquire(holder_id, acquire_timeout)
+                .await
+                .map_err(|e| e.to_string())?;
+            held.insert(lease_name.to_string(), (lease_lock, guard));
+            Ok(None)
+        }
+        ["RELEASE", lease_name] => {
+            let (lease_lock, guard) = held
+                .remove(*lease_name)
+                .ok_or("not held on this connection")?;
+            drop(guard);
+            lease_lock.complete_all_operations().await;
+            Ok(None)
+        }
+        ["HOLDER", lease_name] => {
+            let lease_state = LeaseLock::new(api.clone(), lease_name.to_string())
+                .lease_client()
+                .get_state()
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(Some(lease_state.owner().unwrap_or("-").to_string()))
+        }
+        _ => Err("unknown command".into()),
+    }
+}
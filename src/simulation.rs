@@ -0,0 +1,438 @@
+//! Deterministic simulations of elections through [try_acquire_or_renew_at] over an in-memory
+//! [ResourceLock], enabled by the `test-util` feature. Requests of the candidates are delivered
+//! after random delays, hence reordered, and may fail with injected conflicts, lost responses or
+//! stale reads, all on a virtual clock. Every run is checked for the core invariant: no two
+//! candidates believe they hold the record at overlapping times. Configure further schedules
+//! with the [Scenario] knobs, or check holds collected elsewhere with [check_holds].
+//!
+//! Only the [ResourceLock] election is simulated: [LeaseLock](crate::LeaseLock) and its guards,
+//! renewal and options talk to the Lease API directly, so they are not covered here and are
+//! tested against a real cluster instead.
+
+use crate::lease::{Error, UtcInstant};
+use crate::random::{RandomSource, SeededRandom};
+use crate::resourcelock::{try_acquire_or_renew_at, LeaderElectionRecord, ResourceLock};
+use futures::channel::oneshot;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+type ReadResult = Result<Option<(LeaderElectionRecord, String)>, Error>;
+
+/// Round of [try_acquire_or_renew_at] in progress.
+type Attempt<'a> = BoxFuture<'a, Result<bool, Error>>;
+
+/// Election of candidates contending one record, see [Scenario::run].
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    candidates: usize,
+    lease_duration: Duration,
+    retry_period: Duration,
+    max_delay: Duration,
+    run_for: Duration,
+    conflicts: u64,
+    lost_responses: u64,
+    stale_reads: u64,
+}
+
+/// Time a candidate believes it holds the record, from the start of a successful round of
+/// [try_acquire_or_renew_at] until the lease duration has passed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hold {
+    pub holder: String,
+    pub from: UtcInstant,
+    pub until: UtcInstant,
+}
+
+/// Two candidates believed they held the record at the same time.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "{} held from {} until {}, overlapping {} from {} until {}",
+    .first.holder, .first.from, .first.until, .second.holder, .second.from, .second.until
+)]
+pub struct Overlap {
+    pub first: Hold,
+    pub second: Hold,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            candidates: 3,
+            lease_duration: Duration::from_secs(10),
+            retry_period: Duration::from_secs(2),
+            max_delay: Duration::from_secs(1),
+            run_for: Duration::from_secs(300),
+            conflicts: 0,
+            lost_responses: 0,
+            stale_reads: 0,
+        }
+    }
+}
+
+impl Scenario {
+    /// Number of contending candidates. Default is 3.
+    pub fn with_candidates(mut self, candidates: usize) -> Self {
+        self.candidates = candidates;
+        self
+    }
+
+    /// Lease duration of the candidates. Default is 10 seconds.
+    pub fn with_lease_duration(mut self, duration: Duration) -> Self {
+        self.lease_duration = duration;
+        self
+    }
+
+    /// Wait of a candidate between its rounds. Default is 2 seconds.
+    pub fn with_retry_period(mut self, period: Duration) -> Self {
+        self.retry_period = period;
+        self
+    }
+
+    /// Longest delay of a request, and again of its response. Default is 1 second.
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Virtual time to simulate. Default is 5 minutes.
+    pub fn with_run_for(mut self, run_for: Duration) -> Self {
+        self.run_for = run_for;
+        self
+    }
+
+    /// Fail this many updates per mille with a conflict, although the version matched.
+    pub fn with_conflicts(mut self, per_mille: u64) -> Self {
+        self.conflicts = per_mille;
+        self
+    }
+
+    /// Lose the response of this many applied writes per mille, so that candidates see an error.
+    pub fn with_lost_responses(mut self, per_mille: u64) -> Self {
+        self.lost_responses = per_mille;
+        self
+    }
+
+    /// Answer this many reads per mille with an older state of the record, as a lagging cache
+    /// would.
+    pub fn with_stale_reads(mut self, per_mille: u64) -> Self {
+        self.stale_reads = per_mille;
+        self
+    }
+
+    /// Simulate the election with the schedule drawn from `seed`, and return the holds of all
+    /// candidates, or the first overlap found among them.
+    pub fn run(&self, seed: u64) -> Result<Vec<Hold>, Overlap> {
+        self.run_with(&SeededRandom::new(seed))
+    }
+
+    /// Like [Scenario::run], drawing the schedule from `random`.
+    pub fn run_with(&self, random: &dyn RandomSource) -> Result<Vec<Hold>, Overlap> {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let locks: Vec<_> = (0..self.candidates)
+            .map(|_| SimulatedLock { sent: sent.clone() })
+            .collect();
+        let identities: Vec<_> = (0..self.candidates)
+            .map(|i| format!("candidate-{}", i))
+            .collect();
+        // as stored in the record
+        let held_for = chrono_duration(Duration::from_secs(self.lease_duration.as_secs()));
+        let start = UtcInstant::from(std::time::UNIX_EPOCH);
+        let end = start + chrono_duration(self.run_for);
+        let mut now = start;
+        let mut next_round: Vec<_> = (0..self.candidates)
+            .map(|_| start + self.random_delay(random, self.retry_period))
+            .collect();
+        let mut rounds: Vec<Option<(UtcInstant, Attempt<'_>)>> =
+            (0..self.candidates).map(|_| None).collect();
+        let mut store = Store::default();
+        let mut requests: Vec<(UtcInstant, Request)> = Vec::new();
+        let mut replies: Vec<(UtcInstant, Reply)> = Vec::new();
+        let mut holds = Vec::new();
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        loop {
+            for (i, round) in rounds.iter_mut().enumerate() {
+                if round.is_none() && next_round[i] <= now {
+                    let lock: &dyn ResourceLock = &locks[i];
+                    let attempt =
+                        try_acquire_or_renew_at(lock, &identities[i], self.lease_duration, now);
+                    *round = Some((now, attempt.boxed()));
+                }
+                if let Some((started, attempt)) = round {
+                    if let Poll::Ready(result) = attempt.poll_unpin(&mut cx) {
+                        if let Ok(true) = result {
+                            holds.push(Hold {
+                                holder: identities[i].clone(),
+                                from: *started,
+                                until: *started + held_for,
+                            });
+                        }
+                        next_round[i] = now + chrono_duration(self.retry_period);
+                        *round = None;
+                    }
+                }
+            }
+            for request in sent.lock().unwrap().drain(..) {
+                requests.push((now + self.random_delay(random, self.max_delay), request));
+            }
+            let next = requests
+                .iter()
+                .map(|(at, _)| *at)
+                .chain(replies.iter().map(|(at, _)| *at))
+                .chain(
+                    next_round
+                        .iter()
+                        .zip(&rounds)
+                        .filter(|(_, round)| round.is_none())
+                        .map(|(at, _)| *at),
+                )
+                .min();
+            now = match next {
+                Some(next) if next <= end => next,
+                _ => break,
+            };
+            // requests due at the same time are served in random order
+            loop {
+                let due: Vec<_> = (0..requests.len())
+                    .filter(|&i| requests[i].0 <= now)
+                    .collect();
+                if due.is_empty() {
+                    break;
+                }
+                let pick = due[random.gen_range(due.len() as u64 - 1) as usize];
+                let (_, request) = requests.swap_remove(pick);
+                let reply = store.serve(request, self, random);
+                replies.push((now + self.random_delay(random, self.max_delay), reply));
+            }
+            let (due, pending): (Vec<_>, Vec<_>) =
+                replies.into_iter().partition(|(at, _)| *at <= now);
+            replies = pending;
+            for (_, reply) in due {
+                reply.send();
+            }
+        }
+        check_holds(&holds)?;
+        Ok(holds)
+    }
+
+    fn random_delay(&self, random: &dyn RandomSource, max: Duration) -> chrono::Duration {
+        let max = u64::try_from(max.as_millis()).unwrap_or(u64::MAX);
+        chrono::Duration::milliseconds(random.gen_range(max) as i64)
+    }
+
+    fn chance(random: &dyn RandomSource, per_mille: u64) -> bool {
+        random.gen_range(999) < per_mille
+    }
+}
+
+/// Check that no two holds of different holders overlap.
+pub fn check_holds(holds: &[Hold]) -> Result<(), Overlap> {
+    let mut sorted: Vec<_> = holds.iter().collect();
+    sorted.sort_by_key(|hold| hold.from);
+    for (i, second) in sorted.iter().enumerate() {
+        let overlapped = sorted[..i]
+            .iter()
+            .find(|first| first.holder != second.holder && first.until > second.from);
+        if let Some(first) = overlapped {
+            return Err(Overlap {
+                first: (*first).clone(),
+                second: (*second).clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn chrono_duration(duration: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX)
+}
+
+fn api_error(code: u16, reason: &str) -> Error {
+    Error::from(kube::Error::Api(kube::error::ErrorResponse {
+        status: "Failure".into(),
+        message: format!("simulated {}", reason),
+        reason: reason.into(),
+        code,
+    }))
+}
+
+enum Request {
+    Get(oneshot::Sender<ReadResult>),
+    Create(LeaderElectionRecord, oneshot::Sender<Result<(), Error>>),
+    Update(
+        LeaderElectionRecord,
+        String,
+        oneshot::Sender<Result<(), Error>>,
+    ),
+}
+
+enum Reply {
+    Read(oneshot::Sender<ReadResult>, ReadResult),
+    Write(oneshot::Sender<Result<(), Error>>, Result<(), Error>),
+}
+
+impl Reply {
+    fn send(self) {
+        // the candidate may have been dropped at the end of the run
+        match self {
+            Reply::Read(tx, result) => {
+                let _ = tx.send(result);
+            }
+            Reply::Write(tx, result) => {
+                let _ = tx.send(result);
+            }
+        }
+    }
+}
+
+/// Record as stored by the simulated API server, with its past states for stale reads.
+#[derive(Default)]
+struct Store {
+    object: Option<(LeaderElectionRecord, u64)>,
+    history: Vec<Option<(LeaderElectionRecord, u64)>>,
+    version: u64,
+}
+
+impl Store {
+    fn serve(&mut self, request: Request, scenario: &Scenario, random: &dyn RandomSource) -> Reply {
+        match request {
+            Request::Get(tx) => {
+                let state =
+                    if !self.history.is_empty() && Scenario::chance(random, scenario.stale_reads) {
+                        let index = random.gen_range(self.history.len() as u64 - 1) as usize;
+                        self.history[index].clone()
+                    } else {
+                        self.object.clone()
+                    };
+                let state = state.map(|(record, version)| (record, version.to_string()));
+                Reply::Read(tx, Ok(state))
+            }
+            Request::Create(record, tx) => {
+                let result = if self.object.is_some() {
+                    Err(api_error(409, "AlreadyExists"))
+                } else {
+                    self.write(record);
+                    Self::respond(scenario, random)
+                };
+                Reply::Write(tx, result)
+            }
+            Request::Update(record, version, tx) => {
+                let current = self.object.as_ref().map(|(_, v)| v.to_string());
+                let result = if current.as_deref() != Some(version.as_str())
+                    || Scenario::chance(random, scenario.conflicts)
+                {
+                    Err(api_error(409, "Conflict"))
+                } else {
+                    self.write(record);
+                    Self::respond(scenario, random)
+                };
+                Reply::Write(tx, result)
+            }
+        }
+    }
+
+    fn write(&mut self, record: LeaderElectionRecord) {
+        self.history.push(self.object.take());
+        self.version += 1;
+        self.object = Some((record, self.version));
+    }
+
+    /// Response to an applied write, unless it gets lost.
+    fn respond(scenario: &Scenario, random: &dyn RandomSource) -> Result<(), Error> {
+        if Scenario::chance(random, scenario.lost_responses) {
+            Err(api_error(504, "Timeout"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// [ResourceLock] of a candidate, sending its requests to the simulated API server.
+struct SimulatedLock {
+    sent: Arc<Mutex<Vec<Request>>>,
+}
+
+impl SimulatedLock {
+    fn send<T: Send + 'static>(
+        &self,
+        request: Request,
+        rx: oneshot::Receiver<Result<T, Error>>,
+    ) -> BoxFuture<'static, Result<T, Error>> {
+        self.sent.lock().unwrap().push(request);
+        // unanswered when the run ends
+        async move {
+            rx.await
+                .unwrap_or_else(|_| Err(api_error(503, "ServiceUnavailable")))
+        }
+        .boxed()
+    }
+}
+
+impl ResourceLock for SimulatedLock {
+    fn get(&self) -> BoxFuture<'_, ReadResult> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Request::Get(tx), rx)
+    }
+
+    fn create<'a>(&'a self, record: &'a LeaderElectionRecord) -> BoxFuture<'a, Result<(), Error>> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Request::Create(record.clone(), tx), rx)
+    }
+
+    fn update<'a>(
+        &'a self,
+        record: &'a LeaderElectionRecord,
+        version: &'a str,
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Request::Update(record.clone(), version.to_string(), tx), rx)
+    }
+
+    fn describe(&self) -> String {
+        "Simulated/lease".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulation::*;
+
+    #[test]
+    fn elects_without_faults() {
+        for seed in 0..20 {
+            let holds = Scenario::default().run(seed).unwrap();
+            assert!(!holds.is_empty(), "seed {}: nobody elected", seed);
+        }
+    }
+
+    #[test]
+    fn no_overlap_under_faults() {
+        let scenario = Scenario::default()
+            .with_candidates(5)
+            .with_max_delay(Duration::from_secs(8))
+            .with_conflicts(100)
+            .with_lost_responses(100)
+            .with_stale_reads(200);
+        for seed in 0..50 {
+            if let Err(overlap) = scenario.run(seed) {
+                panic!("seed {}: {}", seed, overlap);
+            }
+        }
+    }
+
+    #[test]
+    fn overlap_detected() {
+        let at = |secs| UtcInstant::from(std::time::UNIX_EPOCH) + chrono::Duration::seconds(secs);
+        let hold = |holder: &str, from, until| Hold {
+            holder: holder.to_string(),
+            from: at(from),
+            until: at(until),
+        };
+        assert!(check_holds(&[hold("a", 0, 10), hold("a", 5, 15), hold("b", 15, 25)]).is_ok());
+        let overlap = check_holds(&[hold("b", 14, 24), hold("a", 0, 15)]).unwrap_err();
+        assert_eq!(overlap.first, hold("a", 0, 15));
+        assert_eq!(overlap.second, hold("b", 14, 24));
+    }
+}
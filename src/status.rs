@@ -0,0 +1,133 @@
+use crate::defaults;
+use crate::lease::{Error, LeaseState};
+use http::StatusCode;
+use kube::api::{ApiResource, DynamicObject, Patch, PatchParams};
+
+/// Retries of a report losing a race with another writer of the object's status.
+const CONFLICT_RETRIES: u32 = 3;
+
+/// Writes leadership of a lease into a status condition of a user-provided custom resource,
+/// so HA state shows up in `kubectl describe` of the operator's own CRD.
+#[derive(Clone)]
+pub struct ConditionReporter {
+    api: kube::Api<DynamicObject>,
+    kind: String,
+    name: String,
+    condition_type: String,
+    field_manager: String,
+}
+
+impl ConditionReporter {
+    /// Report into object `name` of the resource described by `resource`.
+    /// The resource must have a status subresource with a `conditions` list, which may be
+    /// shared with other controllers.
+    pub fn new(api: kube::Api<DynamicObject>, resource: &ApiResource, name: String) -> Self {
+        Self {
+            api,
+            kind: resource.kind.clone(),
+            name,
+            condition_type: "Leader".into(),
+            field_manager: defaults::current().field_manager,
+        }
+    }
+
+    /// Configure condition type. Default is `Leader`.
+    pub fn with_condition_type(mut self, condition_type: impl Into<String>) -> Self {
+        self.condition_type = condition_type.into();
+        self
+    }
+
+    /// Configure field manager used for status patches.
+    /// Default is `lease-rs`, unless changed by [crate::defaults].
+    pub fn with_field_manager(mut self, field_manager: impl Into<String>) -> Self {
+        self.field_manager = field_manager.into();
+        self
+    }
+
+    /// Write the condition for the given lease state: leader id, term (`leaseTransitions`) and
+    /// the time leadership was taken. Other conditions of the object are kept, and so is the
+    /// `lastTransitionTime` of the condition unless its status changes.
+    pub async fn report(&self, lease_state: &LeaseState) -> Result<(), Error> {
+        let (status, reason, message, since) = match lease_state.owner() {
+            Some(leader) => (
+                "True",
+                "LeaderElected",
+                format!(
+                    "{} leads {} (term {})",
+                    leader,
+                    lease_state.lease_name(),
+                    lease_state.transitions()
+                ),
+                lease_state.acquire_time().unwrap_or_else(chrono::Utc::now),
+            ),
+            None => (
+                "False",
+                "NoLeader",
+                format!("{} has no holder", lease_state.lease_name()),
+                chrono::Utc::now(),
+            ),
+        };
+        let since = serde_json::json!(since.to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        let mut retries = 0;
+        loop {
+            let object = self.api.get_status(&self.name).await?;
+            let mut conditions = object
+                .data
+                .pointer("/status/conditions")
+                .and_then(|c| c.as_array())
+                .cloned()
+                .unwrap_or_default();
+            let previous = conditions
+                .iter()
+                .position(|c| c["type"].as_str() == Some(self.condition_type.as_str()))
+                .map(|i| conditions.remove(i));
+            let last_transition_time = match &previous {
+                Some(previous) if previous["status"].as_str() == Some(status) => {
+                    previous["lastTransitionTime"].clone()
+                }
+                _ => since.clone(),
+            };
+            conditions.push(serde_json::json!({
+                "type": &self.condition_type,
+                "status": status,
+                "reason": reason,
+                "message": &message,
+                "lastTransitionTime": last_transition_time,
+            }));
+            // the whole list is written, so guard against concurrent writers of other conditions
+            let patch = serde_json::json!({
+                "metadata": { "resourceVersion": object.metadata.resource_version },
+                "status": { "conditions": conditions },
+            });
+            let params = PatchParams {
+                field_manager: Some(self.field_manager.clone()),
+                ..Default::default()
+            };
+            match self
+                .api
+                .patch_status(&self.name, &params, &Patch::Merge(&patch))
+                .await
+            {
+                Ok(_) => return Ok(()),
+                Err(kube::Error::Api(api_err))
+                    if api_err.code == StatusCode::CONFLICT && retries < CONFLICT_RETRIES =>
+                {
+                    retries += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    pub(crate) async fn report_logged(&self, lease_state: &LeaseState) {
+        if let Err(e) = self.report(lease_state).await {
+            log::error!(
+                "{}.report({}/{}) => {}",
+                lease_state.lease_name(),
+                &self.kind,
+                &self.name,
+                e
+            );
+        }
+    }
+}
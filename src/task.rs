@@ -0,0 +1,66 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+/// Spawn `future` as a background task named `name`, on `runtime` or else the current runtime.
+/// Names show up in tokio-console when built with the `tracing` feature and
+/// `RUSTFLAGS="--cfg tokio_unstable"`.
+pub(crate) fn spawn_named<F>(name: &str, runtime: Option<&Handle>, future: F)
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    #[cfg(all(tokio_unstable, feature = "tracing"))]
+    {
+        let builder = tokio::task::Builder::new().name(name);
+        let spawned = match runtime {
+            Some(runtime) => builder.spawn_on(future, runtime),
+            None => builder.spawn(future),
+        };
+        drop(spawned.expect("spawn task"));
+    }
+    #[cfg(not(all(tokio_unstable, feature = "tracing")))]
+    {
+        let _ = name;
+        // detached: the handle isn't awaited
+        drop(match runtime {
+            Some(runtime) => runtime.spawn(future),
+            None => tokio::spawn(future),
+        });
+    }
+}
+
+/// Operations outliving their caller, e.g. releases scheduled on drop. Each holds a token from
+/// [Completion::token] until done. Clones share the tracking, so it works through `&self`.
+#[derive(Clone)]
+pub(crate) struct Completion {
+    tx: Arc<Mutex<Sender<()>>>,
+    rx: Arc<tokio::sync::Mutex<Receiver<()>>>,
+}
+
+impl Completion {
+    pub(crate) fn new() -> Self {
+        let (tx, rx) = channel(1);
+        Self {
+            tx: Arc::new(Mutex::new(tx)),
+            rx: Arc::new(tokio::sync::Mutex::new(rx)),
+        }
+    }
+
+    /// Token to hold for the duration of an operation.
+    pub(crate) fn token(&self) -> Sender<()> {
+        self.tx.lock().unwrap().clone()
+    }
+
+    /// Wait for all operations started before the call to complete. Operations started meanwhile
+    /// are left to the next call.
+    pub(crate) async fn complete_all(&self) {
+        // Concurrent calls wait one after another, each for its own generation of tokens.
+        let mut rx = self.rx.lock().await;
+        let (next_tx, next_rx) = channel(1);
+        drop(std::mem::replace(&mut *self.tx.lock().unwrap(), next_tx));
+        let _ = rx.recv().await;
+        *rx = next_rx;
+    }
+}
@@ -0,0 +1,51 @@
+//! Helpers for integration tests of downstream crates against a real cluster, enabled by the
+//! `test-util` feature. The crate's own tests are built on them.
+
+use crate::lease::Error;
+use http::StatusCode;
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use kube::api::{DeleteParams, PostParams};
+use rand::Rng;
+
+type Api = kube::Api<LeaseObject>;
+
+/// Random lease name starting with `prefix`, so that concurrent tests don't share leases.
+pub fn random_lease_name(prefix: &str) -> String {
+    format!("{}-{}", prefix, rand::thread_rng().gen::<u32>())
+}
+
+/// Create lease `lease_name` unless it exists already.
+pub async fn create_lease(api: &Api, lease_name: &str) -> Result<(), Error> {
+    let lease: LeaseObject = serde_json::from_value(serde_json::json!({
+        "apiVersion": "coordination.k8s.io/v1",
+        "kind": "Lease",
+        "metadata": { "name": lease_name },
+        "spec": {},
+    }))?;
+    match api.create(&PostParams::default(), &lease).await {
+        Err(kube::Error::Api(api_err)) if api_err.code == StatusCode::CONFLICT => Ok(()),
+        res => res.map(|_| ()).map_err(Error::from),
+    }
+}
+
+/// Delete lease `lease_name`.
+pub async fn delete_lease(api: &Api, lease_name: &str) -> Result<(), Error> {
+    api.delete(lease_name, &DeleteParams::default()).await?;
+    Ok(())
+}
+
+/// Assert that lease `lease_name` is held by `holder`, or by nobody if None.
+pub async fn assert_held_by(api: &Api, lease_name: &str, holder: Option<&str>) {
+    let lease_state = crate::LeaseLock::new(api.clone(), lease_name.to_string())
+        .lease_client()
+        .get_state()
+        .await
+        .expect("get lease");
+    assert_eq!(
+        lease_state.owner(),
+        holder,
+        "lease {} held by {:?}",
+        lease_name,
+        lease_state.holder()
+    );
+}
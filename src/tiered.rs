@@ -0,0 +1,113 @@
+use crate::lease::{Error, LeaseGuard, LeaseLock};
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use std::ops::Deref;
+use std::time::{Duration, Instant};
+
+/// Two-tier election for fleets with many replicas contending one lease: candidates first
+/// pre-elect within their group (e.g. node or zone) on a lease of the group, and only group
+/// winners contend for the global lease. API load is then bounded by the number of groups
+/// rather than the number of replicas.
+pub struct TieredLock {
+    global: LeaseLock,
+    local: LeaseLock,
+    group: String,
+}
+
+/// Guard of both tiers, see [TieredLock::acquire]. Dereferences to the guard of the global lease.
+/// The global lease is released before the local one, so that the next group winner can take
+/// it right away.
+pub struct TieredGuard {
+    global: LeaseGuard,
+    local: LeaseGuard,
+}
+
+impl TieredLock {
+    /// Election for `lease_name` pre-elected within `group`, on a lease named
+    /// `<lease_name>-<group>` created if missing.
+    pub fn new(api: kube::Api<LeaseObject>, lease_name: &str, group: &str) -> Self {
+        Self {
+            local: LeaseLock::new(api.clone(), format!("{}-{}", lease_name, group))
+                .with_create_if_missing(true),
+            global: LeaseLock::new(api, lease_name.to_string()),
+            group: group.to_string(),
+        }
+    }
+
+    /// Configure the [LeaseLock] of the global lease, e.g. `|l| l.with_lease_duration_sec(30)`.
+    pub fn configure_global(mut self, configure: impl FnOnce(LeaseLock) -> LeaseLock) -> Self {
+        self.global = configure(self.global);
+        self
+    }
+
+    /// Configure the [LeaseLock] of the lease of the group.
+    pub fn configure_local(mut self, configure: impl FnOnce(LeaseLock) -> LeaseLock) -> Self {
+        self.local = configure(self.local);
+        self
+    }
+
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    pub fn global(&self) -> &LeaseLock {
+        &self.global
+    }
+
+    pub fn local(&self) -> &LeaseLock {
+        &self.local
+    }
+
+    /// Win the election within the group, then contend for the global lease, both within
+    /// `acquire_timeout`. Should the lease of the group be lost meanwhile, fail with [Error::Lost].
+    pub async fn acquire(
+        &self,
+        holder_id: &str,
+        acquire_timeout: Option<Duration>,
+    ) -> Result<TieredGuard, Error> {
+        let deadline = acquire_timeout.map(|to| Instant::now() + to);
+        let local = self.local.acquire(holder_id, acquire_timeout).await?;
+        log::debug!(
+            "{}.acquire({}) => group {} won",
+            self.global.name(),
+            holder_id,
+            &self.group
+        );
+        let remaining = deadline.map(|d| d.saturating_duration_since(Instant::now()));
+        let global = tokio::select! {
+            global = self.global.acquire(holder_id, remaining) => global?,
+            _ = local.lost() => return Err(Error::Lost),
+        };
+        Ok(TieredGuard { global, local })
+    }
+}
+
+impl TieredGuard {
+    pub fn global(&self) -> &LeaseGuard {
+        &self.global
+    }
+
+    pub fn local(&self) -> &LeaseGuard {
+        &self.local
+    }
+
+    /// Whether both leases are still held, see [LeaseGuard::is_held].
+    pub fn is_held(&self) -> bool {
+        self.global.is_held() && self.local.is_held()
+    }
+
+    /// Resolve once either lease is lost.
+    pub async fn lost(&self) {
+        tokio::select! {
+            _ = self.global.lost() => {}
+            _ = self.local.lost() => {}
+        }
+    }
+}
+
+impl Deref for TieredGuard {
+    type Target = LeaseGuard;
+
+    fn deref(&self) -> &LeaseGuard {
+        &self.global
+    }
+}
@@ -0,0 +1,80 @@
+use crate::lease::{Error, LeaseGuard, UtcInstant};
+use std::fmt;
+use std::str::FromStr;
+
+/// Serializable reference to a held lease, for handing renewal over to another process, see
+/// [LeaseLock::from_token](crate::LeaseLock::from_token). Renders to and parses from JSON.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GuardToken {
+    pub lease_name: String,
+    /// Identity written to the lease.
+    pub holder: String,
+    pub resource_version: String,
+    pub acquire_time: Option<UtcInstant>,
+    pub expires_at: UtcInstant,
+}
+
+impl LeaseGuard {
+    /// Token of the lease as held by this guard.
+    pub fn to_token(&self) -> GuardToken {
+        let lease_state = self.lease_state();
+        GuardToken {
+            lease_name: lease_state.lease_name().to_string(),
+            holder: lease_state.holder().unwrap_or_default().to_string(),
+            resource_version: lease_state.resource_version().to_string(),
+            acquire_time: lease_state.acquire_time(),
+            expires_at: lease_state.expires_at(),
+        }
+    }
+}
+
+impl fmt::Display for GuardToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::json!({
+            "leaseName": &self.lease_name,
+            "holder": &self.holder,
+            "resourceVersion": &self.resource_version,
+            "acquireTime": self.acquire_time,
+            "expiresAt": self.expires_at,
+        });
+        write!(f, "{}", json)
+    }
+}
+
+impl FromStr for GuardToken {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        let json: serde_json::Value = serde_json::from_str(s)?;
+        let field = |key: &str| {
+            json[key]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| Error::Format(key.to_string()))
+        };
+        Ok(GuardToken {
+            lease_name: field("leaseName")?,
+            holder: field("holder")?,
+            resource_version: field("resourceVersion")?,
+            acquire_time: serde_json::from_value(json["acquireTime"].clone())?,
+            expires_at: serde_json::from_value(json["expiresAt"].clone())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token::*;
+
+    #[test]
+    fn round_trip() {
+        let token = GuardToken {
+            lease_name: "lease".into(),
+            holder: "a".into(),
+            resource_version: "42".into(),
+            acquire_time: Some(chrono::Utc::now()),
+            expires_at: chrono::Utc::now(),
+        };
+        assert_eq!(token.to_string().parse::<GuardToken>().unwrap(), token);
+    }
+}
@@ -0,0 +1,89 @@
+//! Renewal latency and error tracking behind
+//! [LeaseLock::suggested_lease_duration](crate::LeaseLock::suggested_lease_duration).
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Renewal attempts kept for the statistics.
+const WINDOW: usize = 200;
+
+/// Fewest attempts before making a suggestion.
+const MIN_SAMPLES: usize = 20;
+
+/// Renewal attempts, at observed tail latency, that should fit between the due time of a
+/// renewal and the expiry of the lease.
+const ATTEMPTS_COVERED: u32 = 3;
+
+#[derive(Default)]
+pub(crate) struct RenewalStats {
+    /// Latencies of recent attempts and whether they succeeded.
+    samples: Mutex<VecDeque<(Duration, bool)>>,
+    warned: AtomicBool,
+}
+
+impl RenewalStats {
+    pub(crate) fn record(&self, latency: Duration, ok: bool) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == WINDOW {
+            samples.pop_front();
+        }
+        samples.push_back((latency, ok));
+    }
+
+    /// Shortest lease duration leaving room for [ATTEMPTS_COVERED] renewal attempts at the
+    /// observed 99th percentile latency, more if attempts fail, given that the `slack` fraction
+    /// of the lease duration is left when a renewal is due. None until enough attempts were
+    /// observed.
+    pub(crate) fn suggest(&self, slack: (u32, u32)) -> Option<Duration> {
+        let samples = self.samples.lock().unwrap();
+        if samples.len() < MIN_SAMPLES {
+            return None;
+        }
+        let mut latencies: Vec<Duration> = samples.iter().map(|(latency, _)| *latency).collect();
+        latencies.sort();
+        let p99 = latencies[(latencies.len() * 99 / 100).min(latencies.len() - 1)];
+        // scale by attempts per success, counting failures as at most 90% of attempts
+        let attempts = samples.len() as u32;
+        let successes = samples.iter().filter(|(_, ok)| *ok).count() as u32;
+        let margin = p99 * ATTEMPTS_COVERED * attempts / successes.max(attempts / 10);
+        Some(margin * slack.1 / slack.0)
+    }
+
+    /// Warn once if `lease_duration` is shorter than suggested.
+    pub(crate) fn check(&self, lease_name: &str, lease_duration: Duration, slack: (u32, u32)) {
+        let suggested = match self.suggest(slack) {
+            Some(suggested) if suggested > lease_duration => suggested,
+            _ => return,
+        };
+        if !self.warned.swap(true, Ordering::Relaxed) {
+            log::warn!(
+                "{}: lease duration {:?} is close to renewal tail latency; suggested {:?}",
+                lease_name,
+                lease_duration,
+                suggested
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tuning::*;
+
+    #[test]
+    fn suggest() {
+        let stats = RenewalStats::default();
+        for _ in 0..MIN_SAMPLES - 1 {
+            stats.record(Duration::from_millis(100), true);
+        }
+        assert_eq!(stats.suggest((3, 5)), None);
+        stats.record(Duration::from_millis(100), true);
+        assert_eq!(stats.suggest((3, 5)), Some(Duration::from_millis(500)));
+        for _ in 0..MIN_SAMPLES {
+            stats.record(Duration::from_millis(100), false);
+        }
+        assert_eq!(stats.suggest((3, 5)), Some(Duration::from_millis(1000)));
+    }
+}
@@ -0,0 +1,47 @@
+use crate::lease::LeaseState;
+use futures::stream::{BoxStream, Stream, StreamExt};
+use k8s_openapi::api::coordination::v1::Lease as LeaseObject;
+use kube::api::ListParams;
+use kube::runtime::watcher::{watcher, Event};
+use std::convert::TryFrom;
+use tokio_retry::strategy::ExponentialBackoff;
+
+/// Endless stream of observed states of a single lease.
+/// Watch expiry (410 Gone) and dropped connections are handled by re-listing, and errors are
+/// retried with `expo` backoff, so long-running observers never silently stop seeing updates.
+/// A deleted lease produces no items until it is created again.
+pub(crate) fn watch_lease(
+    api: kube::Api<LeaseObject>,
+    lease_name: String,
+    expo: ExponentialBackoff,
+) -> impl Stream<Item = LeaseState> + Send + 'static {
+    let lp = ListParams::default().fields(&format!("metadata.name={}", &lease_name));
+    let events: BoxStream<'static, _> = watcher(api, lp).boxed();
+    futures::stream::unfold((events, expo.clone()), move |(mut events, mut backoff)| {
+        let lease_name = lease_name.clone();
+        let expo = expo.clone();
+        async move {
+            loop {
+                let lease_obj = match events.next().await? {
+                    Ok(Event::Applied(lo)) => lo,
+                    Ok(Event::Restarted(los)) => match los.into_iter().last() {
+                        Some(lo) => lo,
+                        None => continue,
+                    },
+                    Ok(Event::Deleted(_)) => continue,
+                    Err(e) => {
+                        let delay = backoff.next().expect("infinite backoff");
+                        log::warn!("{}.watch() => {}:backoff({:?})", &lease_name, e, delay);
+                        tokio::time::sleep(delay).await;
+                        continue;
+                    }
+                };
+                backoff = expo.clone();
+                match LeaseState::try_from(lease_obj) {
+                    Ok(lease_state) => return Some((lease_state, (events, backoff))),
+                    Err(e) => log::error!("{}.watch() => {}", &lease_name, e),
+                }
+            }
+        }
+    })
+}